@@ -0,0 +1,85 @@
+//! Measure how assembly scales with the number of threads
+//!
+//! Usage: `cargo run --release --example scaling_report -- [max_threads] [refinement_level]`
+//!
+//! By default, the report uses up to 2 threads and a sphere with refinement level 2, so that it
+//! runs quickly. The singular and non-singular parts of the operator are assembled and timed
+//! separately in the same run, and the total is the sum of the two. The report is printed as
+//! JSON, with one entry for each number of threads.
+use bempp::boundary_assemblers::BoundaryAssemblerOptions;
+use bempp::function::{FunctionSpace, FunctionSpaceTrait};
+use bempp::laplace::assembler::single_layer;
+use ndelement::ciarlet::LagrangeElementFamily;
+use ndelement::types::Continuity;
+use rlst::Shape;
+use std::time::Instant;
+
+/// Timings for one number of threads
+struct ScalingResult {
+    threads: usize,
+    singular: f64,
+    nonsingular: f64,
+}
+
+fn main() {
+    let _universe = mpi::initialize_with_threading(mpi::Threading::Multiple).unwrap();
+    let comm = mpi::topology::SimpleCommunicator::self_comm();
+
+    let mut args = std::env::args().skip(1);
+    let max_threads = args.next().map_or(2, |a| a.parse().unwrap());
+    let level = args.next().map_or(2, |a| a.parse().unwrap());
+
+    let grid = bempp::shapes::regular_sphere(level, 1, &comm);
+    let element = LagrangeElementFamily::<f64>::new(1, Continuity::Standard);
+    let space = FunctionSpace::new(&grid, &element);
+    let options = BoundaryAssemblerOptions::default();
+    let assembler = single_layer(&options);
+
+    let mut results = vec![];
+    let mut threads = 1;
+    while threads <= max_threads {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap();
+
+        let start = Instant::now();
+        let singular_matrix = pool.install(|| assembler.assemble_singular(&space, &space));
+        let singular = start.elapsed().as_secs_f64();
+
+        let start = Instant::now();
+        let nonsingular_matrix = pool.install(|| assembler.assemble_nonsingular(&space, &space));
+        let nonsingular = start.elapsed().as_secs_f64();
+        assert_eq!(singular_matrix.shape(), nonsingular_matrix.shape());
+
+        results.push(ScalingResult {
+            threads,
+            singular,
+            nonsingular,
+        });
+        threads *= 2;
+    }
+
+    let serial_time = results[0].singular + results[0].nonsingular;
+    println!("{{");
+    println!("  \"operator\": \"laplace_single_layer\",");
+    println!("  \"refinement_level\": {level},");
+    println!("  \"dofs\": {},", space.global_size());
+    println!("  \"results\": [");
+    for (i, r) in results.iter().enumerate() {
+        let total = r.singular + r.nonsingular;
+        let speedup = serial_time / total;
+        println!(
+            "    {{\"threads\": {}, \"total\": {:.6}, \"singular\": {:.6}, \"nonsingular\": {:.6}, \"speedup\": {:.3}, \"efficiency\": {:.3}}}{}",
+            r.threads,
+            total,
+            r.singular,
+            r.nonsingular,
+            speedup,
+            speedup / r.threads as f64,
+            if i + 1 < results.len() { "," } else { "" }
+        );
+    }
+    println!("  ]");
+    println!("}}");
+}
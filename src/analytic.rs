@@ -0,0 +1,172 @@
+//! Analytic solutions for verifying boundary element computations
+//!
+//! The Laplace and Helmholtz Green's functions are taken to be `1 / (4 pi r)` and
+//! `exp(ikr) / (4 pi r)`, matching the kernels used by the assemblers.
+use itertools::izip;
+use ndgrid::types::RealScalar;
+use num::complex::Complex;
+use num::Float;
+
+fn cast<T: RealScalar>(x: f64) -> T {
+    num::cast::<f64, T>(x).unwrap()
+}
+
+fn norm<T: RealScalar>(point: &[T; 3]) -> T {
+    Float::sqrt(point[0] * point[0] + point[1] * point[1] + point[2] * point[2])
+}
+
+/// The potential of a uniformly charged sphere centred at the origin
+///
+/// The total charge is spread uniformly over the surface of the sphere. Outside the sphere the
+/// potential is the same as for a point charge at the origin; inside it is constant.
+pub fn charged_sphere_potential<T: RealScalar>(radius: T, charge: T, point: &[T; 3]) -> T {
+    let r = Float::max(norm(point), radius);
+    charge / (cast::<T>(4.0 * std::f64::consts::PI) * r)
+}
+
+/// The Legendre polynomials of degree 0 to `n` evaluated at `x`
+pub fn legendre_polynomials<T: RealScalar>(n: usize, x: T) -> Vec<T> {
+    let mut p = vec![cast::<T>(1.0), x];
+    for l in 1..n {
+        let l_t = cast::<T>(l as f64);
+        let next = ((cast::<T>(2.0) * l_t + cast::<T>(1.0)) * x * p[l] - l_t * p[l - 1])
+            / (l_t + cast::<T>(1.0));
+        p.push(next);
+    }
+    p.truncate(n + 1);
+    p
+}
+
+/// The real spherical harmonic of degree `l` and order `m` in the direction of a point
+///
+/// The harmonics are orthonormal on the unit sphere. For `m > 0` they are proportional to
+/// `cos(m phi)` and for `m < 0` to `sin(|m| phi)`. The Condon-Shortley phase is included.
+pub fn real_spherical_harmonic<T: RealScalar>(l: usize, m: i32, point: &[T; 3]) -> T {
+    let abs_m = m.unsigned_abs() as usize;
+    assert!(abs_m <= l);
+    let r = norm(point);
+    let cos_theta = point[2] / r;
+    let sin_theta = Float::sqrt(Float::max(
+        cast::<T>(1.0) - cos_theta * cos_theta,
+        cast::<T>(0.0),
+    ));
+    let phi = Float::atan2(point[1], point[0]);
+
+    // Associated Legendre function P_l^|m|(cos theta)
+    let mut p_mm = cast::<T>(1.0);
+    for i in 0..abs_m {
+        p_mm = p_mm * cast::<T>(-(2.0 * i as f64 + 1.0)) * sin_theta;
+    }
+    let mut p = [cast::<T>(0.0), p_mm];
+    for k in abs_m + 1..=l {
+        let next = (cast::<T>(2.0 * k as f64 - 1.0) * cos_theta * p[1]
+            - cast::<T>((k + abs_m - 1) as f64) * p[0])
+            / cast::<T>((k - abs_m) as f64);
+        p = [p[1], next];
+    }
+
+    let factorial_ratio = ((l - abs_m + 1)..=(l + abs_m)).fold(1.0, |f, i| f / i as f64);
+    let normalisation = (2.0 * l as f64 + 1.0) / (4.0 * std::f64::consts::PI) * factorial_ratio;
+    let normalisation = cast::<T>(normalisation.sqrt());
+    let sqrt2 = cast::<T>(std::f64::consts::SQRT_2);
+    let m_phi = cast::<T>(abs_m as f64) * phi;
+    match m {
+        0 => normalisation * p[1],
+        m if m > 0 => sqrt2 * normalisation * p[1] * Float::cos(m_phi),
+        _ => sqrt2 * normalisation * p[1] * Float::sin(m_phi),
+    }
+}
+
+/// The solution of the Laplace equation inside a sphere with a spherical harmonic as Dirichlet data
+///
+/// The sphere is centred at the origin, and the boundary data is
+/// [real_spherical_harmonic]`(l, m, x)`.
+pub fn laplace_interior_harmonic<T: RealScalar>(l: usize, m: i32, radius: T, point: &[T; 3]) -> T {
+    Float::powi(norm(point) / radius, l as i32) * real_spherical_harmonic(l, m, point)
+}
+
+/// The solution of the Laplace equation outside a sphere with a spherical harmonic as Dirichlet data
+///
+/// The sphere is centred at the origin, and the boundary data is
+/// [real_spherical_harmonic]`(l, m, x)`. The solution decays at infinity.
+pub fn laplace_exterior_harmonic<T: RealScalar>(l: usize, m: i32, radius: T, point: &[T; 3]) -> T {
+    Float::powi(radius / norm(point), l as i32 + 1) * real_spherical_harmonic(l, m, point)
+}
+
+/// The spherical Bessel functions of the first kind of order 0 to `n` evaluated at `x > 0`
+///
+/// The values are computed by downward recurrence, which is stable for all orders.
+pub fn spherical_bessel_j<T: RealScalar>(n: usize, x: T) -> Vec<T> {
+    assert!(x > cast::<T>(0.0));
+    let start = n + 20 + num::cast::<T, usize>(Float::ceil(x)).unwrap();
+    let mut values = vec![cast::<T>(0.0); start + 2];
+    values[start] = cast::<T>(1e-10);
+    for l in (1..=start).rev() {
+        values[l - 1] = cast::<T>(2.0 * l as f64 + 1.0) / x * values[l] - values[l + 1];
+        // Rescale to avoid overflow
+        if Float::abs(values[l - 1]) > cast::<T>(1e20) {
+            for v in values[l - 1..].iter_mut() {
+                *v *= cast::<T>(1e-20);
+            }
+        }
+    }
+    let j0 = Float::sin(x) / x;
+    let j1 = Float::sin(x) / (x * x) - Float::cos(x) / x;
+    let scale = if Float::abs(j0) > Float::abs(j1) {
+        j0 / values[0]
+    } else {
+        j1 / values[1]
+    };
+    values.truncate(n + 1);
+    values.iter().map(|v| *v * scale).collect()
+}
+
+/// The spherical Bessel functions of the second kind of order 0 to `n` evaluated at `x > 0`
+///
+/// The values are computed by upward recurrence, which is stable for these functions.
+pub fn spherical_bessel_y<T: RealScalar>(n: usize, x: T) -> Vec<T> {
+    assert!(x > cast::<T>(0.0));
+    let mut values = vec![
+        -Float::cos(x) / x,
+        -Float::cos(x) / (x * x) - Float::sin(x) / x,
+    ];
+    for l in 1..n {
+        let next = cast::<T>(2.0 * l as f64 + 1.0) / x * values[l] - values[l - 1];
+        values.push(next);
+    }
+    values.truncate(n + 1);
+    values
+}
+
+/// The field scattered by a sound-soft sphere centred at the origin
+///
+/// The incident field is the plane wave `exp(ikz)`, and the total field vanishes on the sphere.
+/// The scattered field at a point outside the sphere is computed from the first `nterms` terms
+/// of its Mie series.
+pub fn sound_soft_sphere_scattering<T: RealScalar>(
+    wavenumber: T,
+    radius: T,
+    point: &[T; 3],
+    nterms: usize,
+) -> Complex<T> {
+    let r = norm(point);
+    assert!(r >= radius);
+    let ka = wavenumber * radius;
+    let kr = wavenumber * r;
+    let ja = spherical_bessel_j(nterms, ka);
+    let ya = spherical_bessel_y(nterms, ka);
+    let jr = spherical_bessel_j(nterms, kr);
+    let yr = spherical_bessel_y(nterms, kr);
+    let p = legendre_polynomials(nterms, point[2] / r);
+
+    let mut result = Complex::new(cast::<T>(0.0), cast::<T>(0.0));
+    let mut i_l = Complex::new(cast::<T>(1.0), cast::<T>(0.0));
+    for (l, (ja, ya, jr, yr, p)) in izip!(ja, ya, jr, yr, p).take(nterms).enumerate() {
+        let h_a = Complex::new(ja, ya);
+        let h_r = Complex::new(jr, yr);
+        let coefficient = i_l * -cast::<T>(2.0 * l as f64 + 1.0) * ja / h_a;
+        result += coefficient * h_r * p;
+        i_l *= Complex::new(cast::<T>(0.0), cast::<T>(1.0));
+    }
+    result
+}
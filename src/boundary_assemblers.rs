@@ -1,4 +1,5 @@
 //! Boundary operator assembly
+mod block_system;
 mod cell_pair_assemblers;
 pub(crate) mod helpers;
 pub(crate) mod integrands;
@@ -9,26 +10,30 @@ use crate::boundary_assemblers::cell_pair_assemblers::{
 use crate::boundary_assemblers::helpers::KernelEvaluator;
 use crate::boundary_assemblers::helpers::{equal_grids, RawData2D, RlstArray, SparseMatrixData};
 use crate::function::FunctionSpaceTrait;
+use crate::grid_tools::reference_vertices;
 use bempp_quadrature::duffy::{
     quadrilateral_duffy, quadrilateral_triangle_duffy, triangle_duffy, triangle_quadrilateral_duffy,
 };
 use bempp_quadrature::types::{CellToCellConnectivity, TestTrialNumericalQuadratureDefinition};
 use green_kernels::traits::Kernel;
-use integrands::BoundaryIntegrand;
+use integrands::{BoundaryIntegrand, BoundaryIntegrandWithCoefficients};
 use itertools::izip;
 use ndelement::quadrature::simplex_rule;
 use ndelement::reference_cell;
 use ndelement::traits::FiniteElement;
 use ndelement::types::ReferenceCellType;
-use ndgrid::traits::{Entity, Grid, Topology};
+use ndgrid::traits::{Entity, GeometryMap, Grid, Topology};
 use ndgrid::types::Ownership;
+use num::{One, Zero};
 use rayon::prelude::*;
 use rlst::{
-    rlst_dynamic_array2, rlst_dynamic_array4, CsrMatrix, DefaultIterator, DynamicArray,
-    MatrixInverse, RandomAccessMut, RawAccess, RawAccessMut, RlstScalar, Shape,
+    rlst_dynamic_array2, rlst_dynamic_array3, rlst_dynamic_array4, CsrMatrix, DefaultIterator,
+    DynamicArray, MatrixInverse, RandomAccessMut, RawAccess, RawAccessMut, RlstScalar, Shape,
 };
 use std::collections::HashMap;
 
+pub use block_system::BlockSystem;
+
 /// Options for a boundary assembler
 #[derive(Clone)]
 pub struct BoundaryAssemblerOptions {
@@ -99,9 +104,18 @@ impl BoundaryAssemblerOptions {
     }
 }
 
+/// Quadrature points, weights and element tabulation for a non-singular quadrature rule
+struct RegularQuadratureData<T: RlstScalar> {
+    points: RlstArray<T::Real, 2>,
+    weights: Vec<T::Real>,
+    table: RlstArray<T, 4>,
+}
+
 /// Boundary assembler
 ///
-/// Assembles operators by processing batches of cells in parallel
+/// Assembles operators by processing batches of cells in parallel. The result does not depend on
+/// the number of threads used: batches are combined in a fixed order and cells that share DOFs are
+/// never processed at the same time, so every entry is summed in the same order on each run.
 pub struct BoundaryAssembler<
     'o,
     T: RlstScalar + MatrixInverse,
@@ -154,6 +168,37 @@ impl<'o, T: RlstScalar + MatrixInverse, Integrand: BoundaryIntegrand<T = T>, K:
         }
     }
 
+    /// Assemble the non-singular part into a dense matrix.
+    ///
+    /// Adding the matrix assembled by [BoundaryAssembler::assemble_singular] to this gives the
+    /// matrix assembled by [BoundaryAssembler::assemble].
+    pub fn assemble_nonsingular<Space: FunctionSpaceTrait<T = T> + Sync>(
+        &self,
+        trial_space: &Space,
+        test_space: &Space,
+    ) -> DynamicArray<T, 2> {
+        if !trial_space.is_serial() || !test_space.is_serial() {
+            panic!("Dense assembly can only be used for function spaces stored in serial");
+        }
+
+        let shape = [test_space.global_size(), trial_space.global_size()];
+        let mut output = rlst_dynamic_array2!(T, shape);
+        let output_raw = RawData2D {
+            data: output.data_mut().as_mut_ptr(),
+            shape,
+        };
+        self.assemble_nonsingular_part(
+            &output_raw,
+            trial_space,
+            test_space,
+            &trial_space.cell_colouring(),
+            &test_space.cell_colouring(),
+            &|_, _| false,
+        );
+
+        output
+    }
+
     /// Assemble into a dense matrix.
     pub fn assemble<Space: FunctionSpaceTrait<T = T> + Sync>(
         &self,
@@ -201,6 +246,7 @@ impl<'o, T: RlstScalar + MatrixInverse, Integrand: BoundaryIntegrand<T = T>, K:
             test_space,
             &trial_colouring,
             &test_colouring,
+            &|_, _| false,
         );
 
         let sparse_matrix = self.assemble_singular_part(shape, trial_space, test_space);
@@ -213,6 +259,132 @@ impl<'o, T: RlstScalar + MatrixInverse, Integrand: BoundaryIntegrand<T = T>, K:
         }
     }
 
+    /// Assemble the far-field part of the operator into a dense matrix
+    ///
+    /// Only the interactions between pairs of cells that are further than `separation` apart are
+    /// included, so the result is the discretisation of a smooth kernel. The distance between two
+    /// cells is estimated using spheres that contain the vertices of each cell, so the grids must
+    /// have flat cells: this panics if a cell is curved. The interactions between nearby cells,
+    /// including all singular interactions, are not assembled: the pairs of (test, trial) cells
+    /// that were left out are returned alongside the matrix, so that the near field can be
+    /// handled separately.
+    ///
+    /// `separation` must be non-negative, as cells that share a vertex are never assembled here.
+    ///
+    /// The near pairs are found by checking every pair of cells, so this takes
+    /// `O(n_test_cells * n_trial_cells)` time in addition to the assembly itself. The pairs are
+    /// checked in parallel.
+    pub fn assemble_far_field<Space: FunctionSpaceTrait<T = T> + Sync>(
+        &self,
+        trial_space: &Space,
+        test_space: &Space,
+        separation: T::Real,
+    ) -> (DynamicArray<T, 2>, Vec<[usize; 2]>) {
+        if !trial_space.is_serial() || !test_space.is_serial() {
+            panic!("Dense assembly can only be used for function spaces stored in serial");
+        }
+        assert!(
+            separation >= T::Real::zero(),
+            "Far-field separation must be non-negative"
+        );
+
+        let test_spheres = bounding_spheres(test_space.grid());
+        let trial_spheres = bounding_spheres(trial_space.grid());
+        let is_near = |test_cell: usize, trial_cell: usize| {
+            let (test_centre, test_radius) = &test_spheres[test_cell];
+            let (trial_centre, trial_radius) = &trial_spheres[trial_cell];
+            let distance = test_centre
+                .iter()
+                .zip(trial_centre)
+                .fold(T::Real::zero(), |d, (a, b)| d + (*a - *b) * (*a - *b))
+                .sqrt();
+            distance - *test_radius - *trial_radius <= separation
+        };
+
+        let ntrial_cells = trial_spheres.len();
+        let near_pairs = (0..test_spheres.len())
+            .into_par_iter()
+            .flat_map_iter(|test_cell| {
+                let is_near = &is_near;
+                (0..ntrial_cells)
+                    .filter(move |trial_cell| is_near(test_cell, *trial_cell))
+                    .map(move |trial_cell| [test_cell, trial_cell])
+            })
+            .collect::<Vec<_>>();
+
+        let shape = [test_space.global_size(), trial_space.global_size()];
+        let mut output = rlst_dynamic_array2!(T, shape);
+        let output_raw = RawData2D {
+            data: output.data_mut().as_mut_ptr(),
+            shape,
+        };
+        self.assemble_nonsingular_part(
+            &output_raw,
+            trial_space,
+            test_space,
+            &trial_space.cell_colouring(),
+            &test_space.cell_colouring(),
+            &is_near,
+        );
+
+        (output, near_pairs)
+    }
+
+    /// Multiply the kernel of this assembler by coefficients that depend on the test and trial points
+    ///
+    /// If this assembler uses the kernel `G(x, y)`, the returned assembler uses the kernel
+    /// `c(x) G(x, y) d(y)`, where `c` is `test_coefficient` and `d` is `trial_coefficient`.
+    pub fn with_coefficients<C: Fn(&[T::Real; 3]) -> T + Sync, D: Fn(&[T::Real; 3]) -> T + Sync>(
+        self,
+        test_coefficient: C,
+        trial_coefficient: D,
+    ) -> BoundaryAssembler<'o, T, BoundaryIntegrandWithCoefficients<T, Integrand, C, D>, K> {
+        BoundaryAssembler::new(
+            BoundaryIntegrandWithCoefficients::new(
+                self.integrand,
+                test_coefficient,
+                trial_coefficient,
+            ),
+            self.kernel,
+            self.options,
+            self.deriv_size,
+            self.table_derivs,
+        )
+    }
+
+    /// Evaluate the kernel of this assembler for every pair of a source and a target point
+    ///
+    /// The points are given as three coordinates for each point. The result has shape
+    /// `[n, nsources, ntargets]`, where `n` is 1 if the assembler only uses the value of the
+    /// kernel and 4 if it also uses its derivatives. The entry with first index 0 is the value of
+    /// the kernel and entries 1 to 3 are its derivatives, as computed by the kernel. Coefficients
+    /// added with [BoundaryAssembler::with_coefficients] are not included.
+    pub fn kernel_values(&self, sources: &[T::Real], targets: &[T::Real]) -> DynamicArray<T, 3> {
+        assert_eq!(sources.len() % 3, 0);
+        assert_eq!(targets.len() % 3, 0);
+        let mut values =
+            rlst_dynamic_array3!(T, [self.deriv_size, sources.len() / 3, targets.len() / 3]);
+        self.kernel.assemble_st(sources, targets, values.data_mut());
+        values
+    }
+
+    /// Evaluate the kernel of this assembler for pairs of source and target points
+    ///
+    /// The `i`th source is paired with the `i`th target. The result has shape `[n, npairs]`, with
+    /// the values ordered as in [BoundaryAssembler::kernel_values].
+    pub fn kernel_values_pairwise(
+        &self,
+        sources: &[T::Real],
+        targets: &[T::Real],
+    ) -> DynamicArray<T, 2> {
+        assert_eq!(sources.len() % 3, 0);
+        assert_eq!(sources.len(), targets.len());
+        let mut values = rlst_dynamic_array2!(T, [self.deriv_size, sources.len() / 3]);
+        self.kernel
+            .assemble_pairwise_st(sources, targets, values.data_mut());
+        values
+    }
+
     /// Create new Boundary assembler
     pub(crate) fn new(
         integrand: Integrand,
@@ -392,14 +564,64 @@ impl<'o, T: RlstScalar + MatrixInverse, Integrand: BoundaryIntegrand<T = T>, K:
         )
     }
 
+    /// Get the quadrature points and weights of the non-singular quadrature rule for each cell type
+    /// in the grid of a space, and the tabulation of the space's element at those points
+    fn regular_quadrature_data<Space: FunctionSpaceTrait<T = T>>(
+        &self,
+        space: &Space,
+    ) -> HashMap<ReferenceCellType, RegularQuadratureData<T>> {
+        space
+            .grid()
+            .entity_types(2)
+            .iter()
+            .map(|cell_type| {
+                let npts = self.options.quadrature_degrees[cell_type];
+                let qrule = simplex_rule(*cell_type, npts).unwrap();
+                let mut points = rlst_dynamic_array2!(<T as RlstScalar>::Real, [2, npts]);
+                for i in 0..npts {
+                    for j in 0..2 {
+                        *points.get_mut([j, i]).unwrap() =
+                            num::cast::<f64, <T as RlstScalar>::Real>(qrule.points[2 * i + j])
+                                .unwrap();
+                    }
+                }
+                let weights = qrule
+                    .weights
+                    .iter()
+                    .map(|w| num::cast::<f64, <T as RlstScalar>::Real>(*w).unwrap())
+                    .collect::<Vec<_>>();
+
+                let element = space.element(*cell_type);
+                let mut table =
+                    rlst_dynamic_array4!(T, element.tabulate_array_shape(self.table_derivs, npts));
+                element.tabulate(&points, self.table_derivs, &mut table);
+
+                (
+                    *cell_type,
+                    RegularQuadratureData {
+                        points,
+                        weights,
+                        table,
+                    },
+                )
+            })
+            .collect()
+    }
+
     /// Assemble the non-singular contributions into a dense matrix
-    fn assemble_nonsingular_part<Space: FunctionSpaceTrait<T = T> + Sync>(
+    ///
+    /// Pairs of (test, trial) cells for which `skip` returns true are not assembled.
+    fn assemble_nonsingular_part<
+        Space: FunctionSpaceTrait<T = T> + Sync,
+        Skip: Fn(usize, usize) -> bool + Sync,
+    >(
         &self,
         output: &RawData2D<T>,
         trial_space: &Space,
         test_space: &Space,
         trial_colouring: &HashMap<ReferenceCellType, Vec<Vec<usize>>>,
         test_colouring: &HashMap<ReferenceCellType, Vec<Vec<usize>>>,
+        skip: &Skip,
     ) {
         if !trial_space.is_serial() || !test_space.is_serial() {
             panic!("Dense assembly can only be used for function spaces stored in serial");
@@ -412,56 +634,15 @@ impl<'o, T: RlstScalar + MatrixInverse, Integrand: BoundaryIntegrand<T = T>, K:
 
         let batch_size = self.options.batch_size;
 
+        // Each element is tabulated once for each cell type, and the tabulations are reused for
+        // every pair of cell types
+        let test_data = self.regular_quadrature_data(test_space);
+        let trial_data = self.regular_quadrature_data(trial_space);
+
         for test_cell_type in test_space.grid().entity_types(2) {
-            let npts_test = self.options.quadrature_degrees[test_cell_type];
+            let test = &test_data[test_cell_type];
             for trial_cell_type in trial_space.grid().entity_types(2) {
-                let npts_trial = self.options.quadrature_degrees[trial_cell_type];
-                let qrule_test = simplex_rule(*test_cell_type, npts_test).unwrap();
-                let mut qpoints_test =
-                    rlst_dynamic_array2!(<T as RlstScalar>::Real, [2, npts_test]);
-                for i in 0..npts_test {
-                    for j in 0..2 {
-                        *qpoints_test.get_mut([j, i]).unwrap() =
-                            num::cast::<f64, <T as RlstScalar>::Real>(qrule_test.points[2 * i + j])
-                                .unwrap();
-                    }
-                }
-                let qweights_test = qrule_test
-                    .weights
-                    .iter()
-                    .map(|w| num::cast::<f64, <T as RlstScalar>::Real>(*w).unwrap())
-                    .collect::<Vec<_>>();
-                let qrule_trial = simplex_rule(*trial_cell_type, npts_trial).unwrap();
-                let mut qpoints_trial =
-                    rlst_dynamic_array2!(<T as RlstScalar>::Real, [2, npts_trial]);
-                for i in 0..npts_trial {
-                    for j in 0..2 {
-                        *qpoints_trial.get_mut([j, i]).unwrap() =
-                            num::cast::<f64, <T as RlstScalar>::Real>(
-                                qrule_trial.points[2 * i + j],
-                            )
-                            .unwrap();
-                    }
-                }
-                let qweights_trial = qrule_trial
-                    .weights
-                    .iter()
-                    .map(|w| num::cast::<f64, <T as RlstScalar>::Real>(*w).unwrap())
-                    .collect::<Vec<_>>();
-
-                let test_element = test_space.element(*test_cell_type);
-                let mut test_table = rlst_dynamic_array4!(
-                    T,
-                    test_element.tabulate_array_shape(self.table_derivs, npts_test)
-                );
-                test_element.tabulate(&qpoints_test, self.table_derivs, &mut test_table);
-
-                let trial_element = trial_space.element(*trial_cell_type);
-                let mut trial_table = rlst_dynamic_array4!(
-                    T,
-                    trial_element.tabulate_array_shape(self.table_derivs, npts_trial)
-                );
-                trial_element.tabulate(&qpoints_test, self.table_derivs, &mut trial_table);
+                let trial = &trial_data[trial_cell_type];
 
                 for test_c in &test_colouring[test_cell_type] {
                     for trial_c in &trial_colouring[trial_cell_type] {
@@ -504,12 +685,13 @@ impl<'o, T: RlstScalar + MatrixInverse, Integrand: BoundaryIntegrand<T = T>, K:
                                     trial_cells[t],
                                     test_space,
                                     test_cells[t],
-                                    &qpoints_trial,
-                                    &qweights_trial,
-                                    &qpoints_test,
-                                    &qweights_test,
-                                    &trial_table,
-                                    &test_table,
+                                    &trial.points,
+                                    &trial.weights,
+                                    &test.points,
+                                    &test.weights,
+                                    &trial.table,
+                                    &test.table,
+                                    skip,
                                 )
                             })
                             .sum();
@@ -691,6 +873,7 @@ fn assemble_batch_nonadjacent<
     Space: FunctionSpaceTrait<T = T>,
     Integrand: BoundaryIntegrand<T = T>,
     K: Kernel<T = T>,
+    Skip: Fn(usize, usize) -> bool,
 >(
     assembler: &BoundaryAssembler<T, Integrand, K>,
     deriv_size: usize,
@@ -707,6 +890,7 @@ fn assemble_batch_nonadjacent<
     test_weights: &[T::Real],
     trial_table: &RlstArray<T, 4>,
     test_table: &RlstArray<T, 4>,
+    skip: &Skip,
 ) -> usize {
     let npts_test = test_weights.len();
     let npts_trial = trial_weights.len();
@@ -751,7 +935,9 @@ fn assemble_batch_nonadjacent<
         a.set_trial_cell(*trial_cell);
         let trial_dofs = unsafe { trial_space.cell_dofs_unchecked(*trial_cell) };
         for test_cell in test_cells.iter() {
-            if neighbours(test_grid, trial_grid, *test_cell, *trial_cell) {
+            if neighbours(test_grid, trial_grid, *test_cell, *trial_cell)
+                || skip(*test_cell, *trial_cell)
+            {
                 continue;
             }
 
@@ -772,6 +958,106 @@ fn assemble_batch_nonadjacent<
     1
 }
 
+/// The order of the lattice of points used to check that cells are not curved
+const CURVED_CELL_CHECK_ORDER: usize = 5;
+
+/// Get a sphere containing each cell, indexed by local cell index
+///
+/// The sphere contains the vertices of the cell. This is only a bound for the whole cell if the
+/// cell is flat (or for quadrilaterals, bilinear), so this panics if the geometry of a cell
+/// differs from the interpolation of its vertices at a lattice of points. The lattice is fine
+/// enough to detect curved cells of geometry degree up to [CURVED_CELL_CHECK_ORDER].
+fn bounding_spheres<
+    TReal: RlstScalar<Real = TReal>,
+    G: Grid<T = TReal, EntityDescriptor = ReferenceCellType>,
+>(
+    grid: &G,
+) -> Vec<([TReal; 3], TReal)> {
+    let ncells = grid
+        .entity_types(2)
+        .iter()
+        .map(|t| grid.entity_count(*t))
+        .sum::<usize>();
+    let mut spheres = vec![([TReal::zero(); 3], TReal::zero()); ncells];
+    let tolerance = num::cast::<f64, TReal>(1e-10).unwrap();
+
+    for cell_type in grid.entity_types(2) {
+        // Evaluate the geometry at the vertices followed by a lattice of points
+        let mut reference_points = reference_vertices::<TReal>(*cell_type);
+        let nvertices = reference_points.len() / 2;
+        let n = CURVED_CELL_CHECK_ORDER;
+        for i in 0..=n {
+            for j in 0..=n {
+                if *cell_type == ReferenceCellType::Triangle && i + j > n {
+                    continue;
+                }
+                reference_points.push(num::cast::<f64, TReal>(i as f64 / n as f64).unwrap());
+                reference_points.push(num::cast::<f64, TReal>(j as f64 / n as f64).unwrap());
+            }
+        }
+        let npoints = reference_points.len() / 2;
+        let evaluator = grid.geometry_map(*cell_type, &reference_points);
+        let mut points = vec![TReal::zero(); 3 * npoints];
+
+        for cell in grid.entity_iter(2) {
+            if cell.entity_type() != *cell_type {
+                continue;
+            }
+            evaluator.points(cell.local_index(), &mut points);
+            let vertices = &points[..3 * nvertices];
+            let mut centre = [TReal::zero(); 3];
+            for p in vertices.chunks(3) {
+                for (c, x) in centre.iter_mut().zip(p) {
+                    *c += *x / num::cast::<usize, TReal>(nvertices).unwrap();
+                }
+            }
+            let mut radius = TReal::zero();
+            for p in vertices.chunks(3) {
+                let r = centre
+                    .iter()
+                    .zip(p)
+                    .fold(TReal::zero(), |d, (a, b)| d + (*a - *b) * (*a - *b))
+                    .sqrt();
+                if r > radius {
+                    radius = r;
+                }
+            }
+
+            for (x, p) in reference_points[2 * nvertices..]
+                .chunks(2)
+                .zip(points[3 * nvertices..].chunks(3))
+            {
+                let one = TReal::one();
+                let weights = if *cell_type == ReferenceCellType::Triangle {
+                    vec![one - x[0] - x[1], x[0], x[1]]
+                } else {
+                    vec![
+                        (one - x[0]) * (one - x[1]),
+                        x[0] * (one - x[1]),
+                        (one - x[0]) * x[1],
+                        x[0] * x[1],
+                    ]
+                };
+                let distance = (0..3)
+                    .map(|d| {
+                        let interpolated = weights
+                            .iter()
+                            .zip(vertices.chunks(3))
+                            .fold(TReal::zero(), |v, (w, vertex)| v + *w * vertex[d]);
+                        (p[d] - interpolated) * (p[d] - interpolated)
+                    })
+                    .fold(TReal::zero(), |a, b| a + b)
+                    .sqrt();
+                if distance > tolerance * radius {
+                    panic!("Far-field assembly can only be used for grids with flat cells");
+                }
+            }
+            spheres[cell.local_index()] = (centre, radius);
+        }
+    }
+    spheres
+}
+
 fn get_pairs_if_smallest(
     test_cell: &impl Entity,
     trial_cell: &impl Entity,
@@ -0,0 +1,200 @@
+//! Block systems of boundary integral equations
+use crate::boundary_assemblers::{integrands::BoundaryIntegrand, BoundaryAssembler};
+use crate::function::FunctionSpaceTrait;
+use green_kernels::traits::Kernel;
+use rlst::{
+    rlst_dynamic_array2, CsrMatrix, DynamicArray, MatrixInverse, RawAccess, RawAccessMut,
+    RlstScalar, Shape,
+};
+
+/// A block system of boundary integral equations
+///
+/// Each unknown is associated with the function space it is discretised in, and each equation
+/// with the function space it is tested with. The left-hand side of an equation is a sum of
+/// operators applied to unknowns; the blocks are assembled into a single dense matrix.
+///
+/// Blocks can be added as dense matrices or as sparse CSR matrices (such as the matrices created
+/// by [crate::function::trace_matrix]), but the matrix of the whole system is always dense, so
+/// this is only suitable for systems that are small enough to be stored and solved densely.
+/// Operators that are only available as a matrix-vector product cannot be added.
+pub struct BlockSystem<T: RlstScalar + MatrixInverse> {
+    unknown_sizes: Vec<usize>,
+    equation_sizes: Vec<usize>,
+    terms: Vec<(usize, usize, T, DynamicArray<T, 2>)>,
+    sparse_terms: Vec<(usize, usize, T, CsrMatrix<T>)>,
+    rhs: Vec<Vec<T>>,
+}
+
+impl<T: RlstScalar + MatrixInverse> BlockSystem<T> {
+    /// Create new
+    pub fn new() -> Self {
+        Self {
+            unknown_sizes: vec![],
+            equation_sizes: vec![],
+            terms: vec![],
+            sparse_terms: vec![],
+            rhs: vec![],
+        }
+    }
+
+    /// Add an unknown discretised in a function space and return its index
+    pub fn add_unknown(&mut self, trial_space: &impl FunctionSpaceTrait<T = T>) -> usize {
+        self.unknown_sizes.push(trial_space.global_size());
+        self.unknown_sizes.len() - 1
+    }
+
+    /// Add an equation tested with a function space and return its index
+    pub fn add_equation(&mut self, test_space: &impl FunctionSpaceTrait<T = T>) -> usize {
+        self.equation_sizes.push(test_space.global_size());
+        self.rhs.push(vec![T::zero(); test_space.global_size()]);
+        self.equation_sizes.len() - 1
+    }
+
+    /// Add an unknown with a given number of coefficients and return its index
+    ///
+    /// This can be used for unknowns that are not discretised in a boundary function space, such
+    /// as the values at the vertices of a volume mesh in a coupled FEM-BEM system.
+    pub fn add_unknown_with_size(&mut self, size: usize) -> usize {
+        self.unknown_sizes.push(size);
+        self.unknown_sizes.len() - 1
+    }
+
+    /// Add an equation with a given number of rows and return its index
+    pub fn add_equation_with_size(&mut self, size: usize) -> usize {
+        self.equation_sizes.push(size);
+        self.rhs.push(vec![T::zero(); size]);
+        self.equation_sizes.len() - 1
+    }
+
+    /// Add `coefficient` times an assembled matrix applied to an unknown to the left-hand side of an equation
+    pub fn add_term(
+        &mut self,
+        equation: usize,
+        unknown: usize,
+        coefficient: T,
+        matrix: DynamicArray<T, 2>,
+    ) {
+        if matrix.shape() != [self.equation_sizes[equation], self.unknown_sizes[unknown]] {
+            panic!("Matrix has wrong shape");
+        }
+        self.terms.push((equation, unknown, coefficient, matrix));
+    }
+
+    /// Add `coefficient` times a sparse matrix applied to an unknown to the left-hand side of an equation
+    pub fn add_sparse_term(
+        &mut self,
+        equation: usize,
+        unknown: usize,
+        coefficient: T,
+        matrix: CsrMatrix<T>,
+    ) {
+        if matrix.shape() != [self.equation_sizes[equation], self.unknown_sizes[unknown]] {
+            panic!("Matrix has wrong shape");
+        }
+        self.sparse_terms
+            .push((equation, unknown, coefficient, matrix));
+    }
+
+    /// Assemble an operator and add `coefficient` times it applied to an unknown to the left-hand side of an equation
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_operator<
+        Space: FunctionSpaceTrait<T = T> + Sync,
+        Integrand: BoundaryIntegrand<T = T>,
+        K: Kernel<T = T>,
+    >(
+        &mut self,
+        equation: usize,
+        unknown: usize,
+        coefficient: T,
+        assembler: &BoundaryAssembler<T, Integrand, K>,
+        trial_space: &Space,
+        test_space: &Space,
+    ) {
+        self.add_term(
+            equation,
+            unknown,
+            coefficient,
+            assembler.assemble(trial_space, test_space),
+        );
+    }
+
+    /// Add a vector to the right-hand side of an equation
+    pub fn add_rhs(&mut self, equation: usize, rhs: &[T]) {
+        assert_eq!(rhs.len(), self.equation_sizes[equation]);
+        for (r, v) in self.rhs[equation].iter_mut().zip(rhs) {
+            *r += *v;
+        }
+    }
+
+    /// The shape of the assembled matrix
+    pub fn shape(&self) -> [usize; 2] {
+        [
+            self.equation_sizes.iter().sum(),
+            self.unknown_sizes.iter().sum(),
+        ]
+    }
+
+    /// Assemble the matrix of the system
+    pub fn matrix(&self) -> DynamicArray<T, 2> {
+        let shape = self.shape();
+        let row_offsets = offsets(&self.equation_sizes);
+        let col_offsets = offsets(&self.unknown_sizes);
+
+        let mut matrix = rlst_dynamic_array2!(T, shape);
+        let output = matrix.data_mut();
+        for (equation, unknown, coefficient, block) in &self.terms {
+            let block_shape = block.shape();
+            for (j, col) in block.data().chunks(block_shape[0]).enumerate() {
+                let start = row_offsets[*equation] + shape[0] * (col_offsets[*unknown] + j);
+                for (entry, value) in output[start..start + block_shape[0]].iter_mut().zip(col) {
+                    *entry += *coefficient * *value;
+                }
+            }
+        }
+        for (equation, unknown, coefficient, block) in &self.sparse_terms {
+            for (i, row) in block.indptr().windows(2).enumerate() {
+                for (j, value) in block.indices()[row[0]..row[1]]
+                    .iter()
+                    .zip(&block.data()[row[0]..row[1]])
+                {
+                    let index = row_offsets[*equation] + i + shape[0] * (col_offsets[*unknown] + j);
+                    output[index] += *coefficient * *value;
+                }
+            }
+        }
+        matrix
+    }
+
+    /// Assemble the right-hand side of the system
+    pub fn rhs(&self) -> Vec<T> {
+        self.rhs.concat()
+    }
+
+    /// Split a solution of the system into the coefficients of each unknown
+    pub fn split_solution(&self, solution: &[T]) -> Vec<Vec<T>> {
+        assert_eq!(solution.len(), self.shape()[1]);
+        let col_offsets = offsets(&self.unknown_sizes);
+        self.unknown_sizes
+            .iter()
+            .zip(col_offsets)
+            .map(|(size, offset)| solution[offset..offset + size].to_vec())
+            .collect()
+    }
+}
+
+impl<T: RlstScalar + MatrixInverse> Default for BlockSystem<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The index of the first entry of each block
+fn offsets(sizes: &[usize]) -> Vec<usize> {
+    let mut offsets = Vec::with_capacity(sizes.len());
+    let mut start = 0;
+    for s in sizes {
+        offsets.push(start);
+        start += s;
+    }
+    offsets
+}
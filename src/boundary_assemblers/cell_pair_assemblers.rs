@@ -37,6 +37,7 @@ pub struct SingularCellPairAssembler<
     test_jdet: Vec<T::Real>,
     trial_jdet: Vec<T::Real>,
     weights: &'a [T::Real],
+    weighted_jdets: Vec<T>,
     test_cell: usize,
     trial_cell: usize,
 }
@@ -79,6 +80,7 @@ impl<
             test_jdet: vec![T::Real::zero(); npts],
             trial_jdet: vec![T::Real::zero(); npts],
             weights,
+            weighted_jdets: vec![T::zero(); npts],
             test_cell: 0,
             trial_cell: 0,
         }
@@ -126,10 +128,21 @@ impl<
             &self.trial_jdet,
         );
 
+        // The kernel values and the scaled weights only depend on the cell pair, so they are
+        // computed once here and reused for every pair of basis functions
+        for (wj, wt, test_jdet, trial_jdet) in izip!(
+            self.weighted_jdets.iter_mut(),
+            self.weights,
+            &self.test_jdet,
+            &self.trial_jdet
+        ) {
+            *wj = num::cast::<T::Real, T>(*wt * *test_jdet * *trial_jdet).unwrap();
+        }
+
         for (trial_i, mut col) in local_mat.col_iter_mut().enumerate() {
             for (test_i, entry) in col.iter_mut().enumerate() {
                 *entry = T::zero();
-                for (index, wt) in self.weights.iter().enumerate() {
+                for (index, wj) in self.weighted_jdets.iter().enumerate() {
                     *entry += self.integrand.evaluate_singular(
                         self.test_table,
                         self.trial_table,
@@ -139,13 +152,7 @@ impl<
                         &self.k,
                         &test_geometry,
                         &trial_geometry,
-                    ) * num::cast::<T::Real, T>(
-                        *wt * unsafe {
-                            *self.test_jdet.get_unchecked(index)
-                                * *self.trial_jdet.get_unchecked(index)
-                        },
-                    )
-                    .unwrap();
+                    ) * *wj;
                 }
             }
         }
@@ -174,7 +181,8 @@ pub struct NonsingularCellPairAssemblerWithTestCaching<
     trial_jacobians: RlstArray<T::Real, 2>,
     test_jdet: Vec<Vec<T::Real>>,
     trial_jdet: Vec<T::Real>,
-    test_weights: &'a [T::Real],
+    test_weighted_jdets: Vec<Vec<T>>,
+    trial_weighted_jdets: Vec<T>,
     trial_weights: &'a [T::Real],
     test_cell: usize,
     trial_cell: usize,
@@ -234,6 +242,14 @@ impl<
             test_evaluator.points(*cell, pts.data_mut());
             test_evaluator.jacobians_dets_normals(*cell, j.data_mut(), jdet, n.data_mut())
         }
+        let test_weighted_jdets = test_jdet
+            .iter()
+            .map(|jdet| {
+                izip!(test_weights, jdet)
+                    .map(|(wt, jd)| num::cast::<T::Real, T>(*wt * *jd).unwrap())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
 
         Self {
             integrand,
@@ -250,7 +266,8 @@ impl<
             trial_jacobians: rlst_dynamic_array2!(T::Real, [6, npts_trial]),
             test_jdet,
             trial_jdet: vec![T::Real::zero(); npts_trial],
-            test_weights,
+            test_weighted_jdets,
+            trial_weighted_jdets: vec![T::zero(); npts_trial],
             trial_weights,
             test_cell: 0,
             trial_cell: 0,
@@ -279,6 +296,13 @@ impl<
             &mut self.trial_jdet,
             self.trial_normals.data_mut(),
         );
+        for (wj, wt, jdet) in izip!(
+            self.trial_weighted_jdets.iter_mut(),
+            self.trial_weights,
+            &self.trial_jdet
+        ) {
+            *wj = num::cast::<T::Real, T>(*wt * *jdet).unwrap();
+        }
     }
     pub fn assemble(&mut self, local_mat: &mut RlstArray<T, 2>) {
         self.kernel.assemble_st(
@@ -302,21 +326,14 @@ impl<
             &self.trial_jdet,
         );
 
+        // The kernel is evaluated once per cell pair above and the scaled weights are cached
+        // per cell, so the loops below only evaluate the integrand for each pair of basis functions
+        let test_weighted_jdets = unsafe { self.test_weighted_jdets.get_unchecked(self.test_cell) };
         for (trial_i, mut col) in local_mat.col_iter_mut().enumerate() {
             for (test_i, entry) in col.iter_mut().enumerate() {
                 *entry = T::zero();
-                for (test_index, test_wt) in self.test_weights.iter().enumerate() {
-                    let test_integrand = unsafe {
-                        num::cast::<T::Real, T>(
-                            *test_wt
-                                * *self
-                                    .test_jdet
-                                    .get_unchecked(self.test_cell)
-                                    .get_unchecked(test_index),
-                        )
-                    }
-                    .unwrap();
-                    for (trial_index, trial_wt) in self.trial_weights.iter().enumerate() {
+                for (test_index, test_wj) in test_weighted_jdets.iter().enumerate() {
+                    for (trial_index, trial_wj) in self.trial_weighted_jdets.iter().enumerate() {
                         *entry += self.integrand.evaluate_nonsingular(
                             self.test_table,
                             self.trial_table,
@@ -327,11 +344,8 @@ impl<
                             &self.k,
                             &test_geometry,
                             &trial_geometry,
-                        ) * num::cast::<T::Real, T>(
-                            *trial_wt * unsafe { *self.trial_jdet.get_unchecked(trial_index) },
-                        )
-                        .unwrap()
-                            * test_integrand;
+                        ) * *trial_wj
+                            * *test_wj;
                     }
                 }
             }
@@ -62,6 +62,27 @@ pub trait GeometryAccess {
     /// # Safety
     /// This function uses unsafe memory access
     unsafe fn jdet(&self) -> Self::T;
+    /// Get component `i` of the tangent vector in reference direction `t`
+    ///
+    /// The tangent vectors are the columns of the jacobian.
+    ///
+    /// # Safety
+    /// This function uses unsafe memory access
+    unsafe fn tangent(&self, t: usize, i: usize) -> Self::T {
+        self.jacobian(3 * t + i)
+    }
+    /// Get component `i` of the surface curl of a scalar basis function
+    ///
+    /// The table must contain the first derivatives of the basis function.
+    ///
+    /// # Safety
+    /// This function uses unsafe memory access
+    unsafe fn surface_curl(&self, table: &impl Access2D<T = Self::T>, i: usize) -> Self::T
+    where
+        Self::T: RlstScalar,
+    {
+        (self.tangent(1, i) * table.get(1, 0) - self.tangent(0, i) * table.get(2, 0)) / self.jdet()
+    }
 }
 
 /// Non-singular kernel with 1D access
@@ -315,3 +336,135 @@ unsafe impl<T: RlstScalar, I: BoundaryIntegrand<T = T>> BoundaryIntegrand
                 .evaluate(k, test_table, trial_table, test_geometry, trial_geometry)
     }
 }
+
+/// An integrand multiplied by coefficients that depend on the test and trial points
+///
+/// If the integrand uses the kernel `G(x, y)`, this integrand uses the kernel `c(x) G(x, y) d(y)`,
+/// where `c` is a function of the test point `x` and `d` is a function of the trial point `y`. The
+/// coefficients are evaluated at each quadrature point, so they are applied in the same way to
+/// singular and non-singular contributions.
+pub struct BoundaryIntegrandWithCoefficients<
+    T: RlstScalar,
+    I: BoundaryIntegrand<T = T>,
+    C: Fn(&[T::Real; 3]) -> T + Sync,
+    D: Fn(&[T::Real; 3]) -> T + Sync,
+> {
+    integrand: I,
+    test_coefficient: C,
+    trial_coefficient: D,
+}
+
+impl<
+        T: RlstScalar,
+        I: BoundaryIntegrand<T = T>,
+        C: Fn(&[T::Real; 3]) -> T + Sync,
+        D: Fn(&[T::Real; 3]) -> T + Sync,
+    > BoundaryIntegrandWithCoefficients<T, I, C, D>
+{
+    /// Create new
+    pub fn new(integrand: I, test_coefficient: C, trial_coefficient: D) -> Self {
+        Self {
+            integrand,
+            test_coefficient,
+            trial_coefficient,
+        }
+    }
+}
+
+unsafe impl<
+        T: RlstScalar,
+        I: BoundaryIntegrand<T = T>,
+        C: Fn(&[T::Real; 3]) -> T + Sync,
+        D: Fn(&[T::Real; 3]) -> T + Sync,
+    > BoundaryIntegrand for BoundaryIntegrandWithCoefficients<T, I, C, D>
+{
+    type T = T;
+
+    fn evaluate(
+        &self,
+        k: &impl Access1D<T = T>,
+        test_table: &impl Access2D<T = T>,
+        trial_table: &impl Access2D<T = T>,
+        test_geometry: &impl GeometryAccess<T = T>,
+        trial_geometry: &impl GeometryAccess<T = T>,
+    ) -> T {
+        let (x, y) = unsafe {
+            (
+                [
+                    test_geometry.point(0).re(),
+                    test_geometry.point(1).re(),
+                    test_geometry.point(2).re(),
+                ],
+                [
+                    trial_geometry.point(0).re(),
+                    trial_geometry.point(1).re(),
+                    trial_geometry.point(2).re(),
+                ],
+            )
+        };
+        (self.test_coefficient)(&x)
+            * self
+                .integrand
+                .evaluate(k, test_table, trial_table, test_geometry, trial_geometry)
+            * (self.trial_coefficient)(&y)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    /// The flat triangle with vertices (0, 0, 0), (2, 0, 0) and (0, 1, 0)
+    struct FlatTriangle;
+
+    impl GeometryAccess for FlatTriangle {
+        type T = f64;
+        unsafe fn point(&self, _i: usize) -> f64 {
+            0.0
+        }
+        unsafe fn normal(&self, i: usize) -> f64 {
+            [0.0, 0.0, 1.0][i]
+        }
+        unsafe fn jacobian(&self, i: usize) -> f64 {
+            [2.0, 0.0, 0.0, 0.0, 1.0, 0.0][i]
+        }
+        unsafe fn jdet(&self) -> f64 {
+            2.0
+        }
+    }
+
+    /// The value and first derivatives of a basis function on the reference triangle
+    struct Derivatives([f64; 3]);
+
+    impl Access2D for Derivatives {
+        type T = f64;
+        unsafe fn get(&self, i: usize, _j: usize) -> f64 {
+            self.0[i]
+        }
+    }
+
+    #[test]
+    fn test_tangent_and_surface_curl() {
+        let geometry = FlatTriangle;
+        unsafe {
+            for (t, tangent) in [[2.0, 0.0, 0.0], [0.0, 1.0, 0.0]].iter().enumerate() {
+                for (i, value) in tangent.iter().enumerate() {
+                    assert_relative_eq!(geometry.tangent(t, i), *value);
+                }
+            }
+
+            // On the triangle, the basis functions xi_0 and xi_1 are x / 2 and y, so their surface
+            // curls n x grad are (0, 1/2, 0) and (-1, 0, 0)
+            for (derivatives, curl) in [
+                ([0.0, 1.0, 0.0], [0.0, 0.5, 0.0]),
+                ([0.0, 0.0, 1.0], [-1.0, 0.0, 0.0]),
+            ] {
+                let table = Derivatives(derivatives);
+                for (i, value) in curl.iter().enumerate() {
+                    assert_relative_eq!(geometry.surface_curl(&table, i), *value);
+                }
+            }
+        }
+    }
+}
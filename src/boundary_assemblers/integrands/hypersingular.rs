@@ -37,22 +37,13 @@ unsafe impl<T: RlstScalar> BoundaryIntegrand for HypersingularCurlCurlBoundaryIn
         trial_geometry: &impl GeometryAccess<T = T>,
     ) -> T {
         unsafe {
-            let test0 = test_table.get(1, 0);
-            let test1 = test_table.get(2, 0);
-            let trial0 = trial_table.get(1, 0);
-            let trial1 = trial_table.get(2, 0);
-
             k.get(0)
-                * ((test_geometry.jacobian(3) * test0 - test_geometry.jacobian(0) * test1)
-                    * (trial_geometry.jacobian(3) * trial0 - trial_geometry.jacobian(0) * trial1)
-                    + (test_geometry.jacobian(4) * test0 - test_geometry.jacobian(1) * test1)
-                        * (trial_geometry.jacobian(4) * trial0
-                            - trial_geometry.jacobian(1) * trial1)
-                    + (test_geometry.jacobian(5) * test0 - test_geometry.jacobian(2) * test1)
-                        * (trial_geometry.jacobian(5) * trial0
-                            - trial_geometry.jacobian(2) * trial1))
-                / test_geometry.jdet()
-                / trial_geometry.jdet()
+                * (test_geometry.surface_curl(test_table, 0)
+                    * trial_geometry.surface_curl(trial_table, 0)
+                    + test_geometry.surface_curl(test_table, 1)
+                        * trial_geometry.surface_curl(trial_table, 1)
+                    + test_geometry.surface_curl(test_table, 2)
+                        * trial_geometry.surface_curl(trial_table, 2))
         }
     }
 }
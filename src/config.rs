@@ -0,0 +1,240 @@
+//! Configuration of boundary element runs
+//!
+//! A [RunConfig] collects the parameters of a run in one place. With the `serde` feature enabled,
+//! it can be written to and read from any format supported by serde, such as JSON or TOML, so
+//! that runs can be reproduced and parameter sweeps driven from files.
+use crate::boundary_assemblers::BoundaryAssemblerOptions;
+use crate::solvers::SolverOptions;
+use crate::{helmholtz, laplace};
+use ndelement::types::ReferenceCellType;
+use rlst::{MatrixInverse, RlstScalar};
+
+/// The kernel used by the operators of a run
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum KernelConfig {
+    /// The Laplace kernel
+    Laplace,
+    /// The Helmholtz kernel
+    Helmholtz {
+        /// The wavenumber
+        wavenumber: f64,
+    },
+}
+
+impl KernelConfig {
+    /// The wavenumber of the kernel
+    ///
+    /// The Laplace kernel is the Helmholtz kernel with wavenumber zero.
+    pub fn wavenumber(&self) -> f64 {
+        match self {
+            Self::Laplace => 0.0,
+            Self::Helmholtz { wavenumber } => *wavenumber,
+        }
+    }
+}
+
+/// The parameters of a boundary element run
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RunConfig {
+    /// The kernel
+    pub kernel: KernelConfig,
+    /// Number of points used in quadrature for non-singular integrals on triangles
+    pub triangle_quadrature_degree: usize,
+    /// Number of points used in quadrature for non-singular integrals on quadrilaterals
+    pub quadrilateral_quadrature_degree: usize,
+    /// Quadrature degree used for singular integrals between two triangles
+    pub triangle_triangle_singular_quadrature_degree: usize,
+    /// Quadrature degree used for singular integrals between a test triangle and a trial quadrilateral
+    pub triangle_quadrilateral_singular_quadrature_degree: usize,
+    /// Quadrature degree used for singular integrals between a test quadrilateral and a trial triangle
+    pub quadrilateral_triangle_singular_quadrature_degree: usize,
+    /// Quadrature degree used for singular integrals between two quadrilaterals
+    pub quadrilateral_quadrilateral_singular_quadrature_degree: usize,
+    /// Maximum size of each batch of cells to send to an assembly function
+    pub batch_size: usize,
+    /// Relative residual at which the iterative solver stops
+    pub solver_tolerance: f64,
+    /// Maximum number of iterations of the iterative solver
+    pub solver_max_iterations: usize,
+}
+
+impl Default for RunConfig {
+    fn default() -> Self {
+        use ReferenceCellType::{Quadrilateral, Triangle};
+        let options = BoundaryAssemblerOptions::default();
+        let solver_options = SolverOptions::<f64>::default();
+        Self {
+            kernel: KernelConfig::Laplace,
+            triangle_quadrature_degree: options.get_regular_quadrature_degree(Triangle).unwrap(),
+            quadrilateral_quadrature_degree: options
+                .get_regular_quadrature_degree(Quadrilateral)
+                .unwrap(),
+            triangle_triangle_singular_quadrature_degree: options
+                .get_singular_quadrature_degree((Triangle, Triangle))
+                .unwrap(),
+            triangle_quadrilateral_singular_quadrature_degree: options
+                .get_singular_quadrature_degree((Triangle, Quadrilateral))
+                .unwrap(),
+            quadrilateral_triangle_singular_quadrature_degree: options
+                .get_singular_quadrature_degree((Quadrilateral, Triangle))
+                .unwrap(),
+            quadrilateral_quadrilateral_singular_quadrature_degree: options
+                .get_singular_quadrature_degree((Quadrilateral, Quadrilateral))
+                .unwrap(),
+            batch_size: options.get_batch_size(),
+            solver_tolerance: solver_options.get_tolerance(),
+            solver_max_iterations: solver_options.get_max_iterations(),
+        }
+    }
+}
+
+impl RunConfig {
+    /// Create the options for the boundary assemblers of the run
+    pub fn assembler_options(&self) -> BoundaryAssemblerOptions {
+        use ReferenceCellType::{Quadrilateral, Triangle};
+        let mut options = BoundaryAssemblerOptions::default();
+        options.set_regular_quadrature_degree(Triangle, self.triangle_quadrature_degree);
+        options.set_regular_quadrature_degree(Quadrilateral, self.quadrilateral_quadrature_degree);
+        options.set_singular_quadrature_degree(
+            (Triangle, Triangle),
+            self.triangle_triangle_singular_quadrature_degree,
+        );
+        options.set_singular_quadrature_degree(
+            (Triangle, Quadrilateral),
+            self.triangle_quadrilateral_singular_quadrature_degree,
+        );
+        options.set_singular_quadrature_degree(
+            (Quadrilateral, Triangle),
+            self.quadrilateral_triangle_singular_quadrature_degree,
+        );
+        options.set_singular_quadrature_degree(
+            (Quadrilateral, Quadrilateral),
+            self.quadrilateral_quadrilateral_singular_quadrature_degree,
+        );
+        options.set_batch_size(self.batch_size);
+        options
+    }
+
+    /// Create the options for the iterative solver of the run
+    pub fn solver_options<R: RlstScalar<Real = R>>(&self) -> SolverOptions<R> {
+        let mut options = SolverOptions::default();
+        options.set_tolerance(num::cast::<f64, R>(self.solver_tolerance).unwrap());
+        options.set_max_iterations(self.solver_max_iterations);
+        options
+    }
+
+    /// Create the Laplace single layer assembler of the run
+    ///
+    /// `options` would usually be created by [RunConfig::assembler_options]. This panics if the
+    /// kernel of the run is not [KernelConfig::Laplace]; runs with the Helmholtz kernel should use
+    /// [RunConfig::helmholtz_single_layer].
+    pub fn laplace_single_layer<'o, T: RlstScalar<Real = T> + MatrixInverse>(
+        &self,
+        options: &'o BoundaryAssemblerOptions,
+    ) -> laplace::assembler::SingleLayer3dAssembler<'o, T> {
+        self.check_laplace();
+        laplace::assembler::single_layer(options)
+    }
+
+    /// Create the Laplace double layer assembler of the run
+    ///
+    /// See [RunConfig::laplace_single_layer].
+    pub fn laplace_double_layer<'o, T: RlstScalar<Real = T> + MatrixInverse>(
+        &self,
+        options: &'o BoundaryAssemblerOptions,
+    ) -> laplace::assembler::DoubleLayer3dAssembler<'o, T> {
+        self.check_laplace();
+        laplace::assembler::double_layer(options)
+    }
+
+    /// Create the Laplace adjoint double layer assembler of the run
+    ///
+    /// See [RunConfig::laplace_single_layer].
+    pub fn laplace_adjoint_double_layer<'o, T: RlstScalar<Real = T> + MatrixInverse>(
+        &self,
+        options: &'o BoundaryAssemblerOptions,
+    ) -> laplace::assembler::AdjointDoubleLayer3dAssembler<'o, T> {
+        self.check_laplace();
+        laplace::assembler::adjoint_double_layer(options)
+    }
+
+    /// Create the Laplace hypersingular assembler of the run
+    ///
+    /// See [RunConfig::laplace_single_layer].
+    pub fn laplace_hypersingular<'o, T: RlstScalar<Real = T> + MatrixInverse>(
+        &self,
+        options: &'o BoundaryAssemblerOptions,
+    ) -> laplace::assembler::Hypersingular3dAssembler<'o, T> {
+        self.check_laplace();
+        laplace::assembler::hypersingular(options)
+    }
+
+    /// Create the Helmholtz single layer assembler of the run
+    ///
+    /// `options` would usually be created by [RunConfig::assembler_options]. The assembler uses the
+    /// wavenumber of [RunConfig::kernel]. This panics if the kernel of the run is not
+    /// [KernelConfig::Helmholtz]; runs with the Laplace kernel should use
+    /// [RunConfig::laplace_single_layer], which assembles in real arithmetic.
+    pub fn helmholtz_single_layer<'o, T: RlstScalar<Complex = T> + MatrixInverse>(
+        &self,
+        options: &'o BoundaryAssemblerOptions,
+    ) -> helmholtz::assembler::SingleLayer3dAssembler<'o, T> {
+        helmholtz::assembler::single_layer(self.helmholtz_wavenumber::<T>(), options)
+    }
+
+    /// Create the Helmholtz double layer assembler of the run
+    ///
+    /// See [RunConfig::helmholtz_single_layer].
+    pub fn helmholtz_double_layer<'o, T: RlstScalar<Complex = T> + MatrixInverse>(
+        &self,
+        options: &'o BoundaryAssemblerOptions,
+    ) -> helmholtz::assembler::DoubleLayer3dAssembler<'o, T> {
+        helmholtz::assembler::double_layer(self.helmholtz_wavenumber::<T>(), options)
+    }
+
+    /// Create the Helmholtz adjoint double layer assembler of the run
+    ///
+    /// See [RunConfig::helmholtz_single_layer].
+    pub fn helmholtz_adjoint_double_layer<'o, T: RlstScalar<Complex = T> + MatrixInverse>(
+        &self,
+        options: &'o BoundaryAssemblerOptions,
+    ) -> helmholtz::assembler::AdjointDoubleLayer3dAssembler<'o, T> {
+        helmholtz::assembler::adjoint_double_layer(self.helmholtz_wavenumber::<T>(), options)
+    }
+
+    /// Create the Helmholtz hypersingular assembler of the run
+    ///
+    /// See [RunConfig::helmholtz_single_layer].
+    pub fn helmholtz_hypersingular<'o, T: RlstScalar<Complex = T> + MatrixInverse>(
+        &self,
+        options: &'o BoundaryAssemblerOptions,
+    ) -> helmholtz::assembler::Hypersingular3dAssembler<'o, T> {
+        helmholtz::assembler::hypersingular(self.helmholtz_wavenumber::<T>(), options)
+    }
+
+    /// Panic if the kernel of the run is not the Laplace kernel
+    fn check_laplace(&self) {
+        if self.kernel != KernelConfig::Laplace {
+            panic!(
+                "Laplace assembler requested for a run with kernel {:?}",
+                self.kernel
+            );
+        }
+    }
+
+    /// The wavenumber of the Helmholtz kernel, converted to the real type of `T`
+    ///
+    /// This panics if the kernel of the run is not the Helmholtz kernel.
+    fn helmholtz_wavenumber<T: RlstScalar>(&self) -> T::Real {
+        match self.kernel {
+            KernelConfig::Helmholtz { wavenumber } => {
+                num::cast::<f64, T::Real>(wavenumber).unwrap()
+            }
+            KernelConfig::Laplace => {
+                panic!("Helmholtz assembler requested for a run with the Laplace kernel")
+            }
+        }
+    }
+}
@@ -0,0 +1,169 @@
+//! Fingerprints of assembled operators for regression testing
+use rlst::{DynamicArray, RawAccess, RlstScalar, Shape};
+use std::fmt;
+use std::str::FromStr;
+
+/// A fingerprint of an assembled operator
+///
+/// The fingerprint records the shape of a matrix, its Frobenius norm, a deterministic sample of
+/// its entries and a hash of all the entries rounded relative to a tolerance. Fingerprints can be
+/// written to a string with [`fmt::Display`] and read back with [`FromStr`], so that they can be
+/// stored alongside tests and used to detect changes to assembled values across releases.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OperatorFingerprint {
+    /// Shape of the matrix
+    pub shape: [usize; 2],
+    /// Relative tolerance used when rounding entries and comparing fingerprints
+    pub tolerance: f64,
+    /// Frobenius norm of the matrix
+    pub norm: f64,
+    /// Hash of the entries of the matrix rounded relative to the tolerance
+    pub hash: u64,
+    /// Sampled entries: the row and column, and the real and imaginary parts of the value
+    pub samples: Vec<([usize; 2], [f64; 2])>,
+}
+
+impl OperatorFingerprint {
+    /// Create the fingerprint of a matrix, sampling `nsamples` entries
+    pub fn new<T: RlstScalar>(
+        matrix: &DynamicArray<T, 2>,
+        nsamples: usize,
+        tolerance: f64,
+    ) -> Self {
+        let shape = matrix.shape();
+        let data = matrix.data();
+        let norm = data
+            .iter()
+            .map(|v| num::cast::<T::Real, f64>(v.abs()).unwrap().powi(2))
+            .sum::<f64>()
+            .sqrt();
+        let max_abs = data
+            .iter()
+            .map(|v| num::cast::<T::Real, f64>(v.abs()).unwrap())
+            .fold(0.0, f64::max);
+
+        // FNV-1a is used rather than the standard library hasher as its output is stable across
+        // Rust versions
+        let quantum = if max_abs > 0.0 {
+            tolerance * max_abs
+        } else {
+            1.0
+        };
+        let mut hash = 0xcbf29ce484222325u64;
+        for v in data {
+            for part in [v.re(), v.im()] {
+                let rounded = (num::cast::<T::Real, f64>(part).unwrap() / quantum).round() as i64;
+                for byte in rounded.to_le_bytes() {
+                    hash ^= byte as u64;
+                    hash = hash.wrapping_mul(0x100000001b3);
+                }
+            }
+        }
+
+        let nsamples = std::cmp::min(nsamples, data.len());
+        let samples = (0..nsamples)
+            .map(|k| {
+                let index = k * data.len() / nsamples;
+                (
+                    [index % shape[0], index / shape[0]],
+                    [
+                        num::cast::<T::Real, f64>(data[index].re()).unwrap(),
+                        num::cast::<T::Real, f64>(data[index].im()).unwrap(),
+                    ],
+                )
+            })
+            .collect::<Vec<_>>();
+
+        Self {
+            shape,
+            tolerance,
+            norm,
+            hash,
+            samples,
+        }
+    }
+
+    /// Check if another fingerprint agrees with this one
+    ///
+    /// The fingerprints must have the same shape, tolerance, hash and sampled entry positions. The
+    /// norms must agree up to the tolerance relative to the larger norm, and each sampled entry
+    /// must agree up to the tolerance relative to the larger magnitude of the two entries, so the
+    /// comparison is symmetric. As the hash is computed from rounded entries, an entry that lies
+    /// close to a rounding boundary can cause a mismatch even if it changed by less than the
+    /// tolerance.
+    pub fn matches(&self, other: &Self) -> bool {
+        self.shape == other.shape
+            && self.tolerance == other.tolerance
+            && self.hash == other.hash
+            && (self.norm - other.norm).abs() <= self.tolerance * f64::max(self.norm, other.norm)
+            && self.samples.len() == other.samples.len()
+            && self
+                .samples
+                .iter()
+                .zip(&other.samples)
+                .all(|((i, a), (j, b))| {
+                    let magnitude = f64::max(a[0].hypot(a[1]), b[0].hypot(b[1]));
+                    i == j && (a[0] - b[0]).hypot(a[1] - b[1]) <= self.tolerance * magnitude
+                })
+    }
+}
+
+impl fmt::Display for OperatorFingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "shape {} {}", self.shape[0], self.shape[1])?;
+        writeln!(f, "tolerance {:e}", self.tolerance)?;
+        writeln!(f, "norm {:e}", self.norm)?;
+        writeln!(f, "hash {}", self.hash)?;
+        for ([i, j], [re, im]) in &self.samples {
+            writeln!(f, "sample {i} {j} {re:e} {im:e}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for OperatorFingerprint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        let mut shape = None;
+        let mut tolerance = None;
+        let mut norm = None;
+        let mut hash = None;
+        let mut samples = vec![];
+
+        for line in s.lines().map(|l| l.trim()).filter(|l| !l.is_empty()) {
+            let words = line.split_whitespace().collect::<Vec<_>>();
+            let parse_error = || format!("Could not parse line: {line}");
+            match (words[0], words.len()) {
+                ("shape", 3) => {
+                    shape = Some([
+                        words[1].parse().map_err(|_| parse_error())?,
+                        words[2].parse().map_err(|_| parse_error())?,
+                    ])
+                }
+                ("tolerance", 2) => tolerance = Some(words[1].parse().map_err(|_| parse_error())?),
+                ("norm", 2) => norm = Some(words[1].parse().map_err(|_| parse_error())?),
+                ("hash", 2) => hash = Some(words[1].parse().map_err(|_| parse_error())?),
+                ("sample", 5) => samples.push((
+                    [
+                        words[1].parse().map_err(|_| parse_error())?,
+                        words[2].parse().map_err(|_| parse_error())?,
+                    ],
+                    [
+                        words[3].parse().map_err(|_| parse_error())?,
+                        words[4].parse().map_err(|_| parse_error())?,
+                    ],
+                )),
+                _ => return Err(parse_error()),
+            }
+        }
+
+        Ok(Self {
+            shape: shape.ok_or("Missing shape")?,
+            tolerance: tolerance.ok_or("Missing tolerance")?,
+            norm: norm.ok_or("Missing norm")?,
+            hash: hash.ok_or("Missing hash")?,
+            samples,
+        })
+    }
+}
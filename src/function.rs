@@ -1,16 +1,23 @@
 //! Functions and function spaces
 
 //mod function_space;
+mod functionals;
+
+pub use functionals::{
+    centre_of_charge, first_moments, integral, mean_value, multipole_moments, normal_dipole_moment,
+    patch_integral, MultipoleMoments,
+};
 
 use mpi::request::WaitGuard;
-use mpi::traits::{Communicator, Destination, Source};
+use mpi::traits::{Communicator, Destination, Equivalence, Source};
 use ndelement::ciarlet::CiarletElement;
 use ndelement::traits::ElementFamily;
 use ndelement::{traits::FiniteElement, types::ReferenceCellType};
 use ndgrid::traits::ParallelGrid;
 use ndgrid::traits::{Entity, Topology};
 use ndgrid::{traits::Grid, types::Ownership};
-use rlst::{MatrixInverse, RlstScalar};
+use num::{One, Zero};
+use rlst::{CsrMatrix, MatrixInverse, RlstScalar};
 use std::collections::HashMap;
 use std::marker::PhantomData;
 
@@ -110,6 +117,54 @@ impl<
             FiniteElement = CiarletElement<T>,
             CellType = ReferenceCellType,
         >,
+    ) -> Self {
+        Self::create(grid, e_family, None)
+    }
+
+    /// Create new function space whose functions can be discontinuous between regions of the grid
+    ///
+    /// `cell_regions` gives the region of each cell. Entities shared by cells in different regions
+    /// get a separate set of DOFs for each region, so functions in the space are continuous within
+    /// each region (as far as the element family is) but can jump across the interfaces between
+    /// regions. As every pair of cells is still on the same grid, singular integrals between
+    /// neighbouring cells in different regions are computed correctly by the assemblers.
+    ///
+    /// `cell_regions` must have one entry for each cell of the local grid, indexed by local cell
+    /// index. This is currently only implemented for grids stored in serial.
+    pub fn new_with_regions(
+        grid: &'a GridImpl,
+        e_family: &impl ElementFamily<
+            T = T,
+            FiniteElement = CiarletElement<T>,
+            CellType = ReferenceCellType,
+        >,
+        cell_regions: &[usize],
+    ) -> Self {
+        if grid.comm().size() != 1 {
+            panic!("Function spaces with regions can only be created on grids stored in serial");
+        }
+        let local_grid = grid.local_grid();
+        let ncells = local_grid
+            .entity_types(local_grid.topology_dim())
+            .iter()
+            .map(|&i| local_grid.entity_count(i))
+            .sum::<usize>();
+        assert_eq!(
+            cell_regions.len(),
+            ncells,
+            "cell_regions must have one entry for each cell of the local grid"
+        );
+        Self::create(grid, e_family, Some(cell_regions))
+    }
+
+    fn create(
+        grid: &'a GridImpl,
+        e_family: &impl ElementFamily<
+            T = T,
+            FiniteElement = CiarletElement<T>,
+            CellType = ReferenceCellType,
+        >,
+        cell_regions: Option<&[usize]>,
     ) -> Self {
         let comm = grid.comm();
         let rank = comm.rank();
@@ -117,7 +172,7 @@ impl<
 
         // Create local space on current process
         let (cell_dofs, entity_dofs, dofmap_size, owner_data) =
-            assign_dofs(rank as usize, grid.local_grid(), e_family);
+            assign_dofs_in_regions(rank as usize, grid.local_grid(), e_family, cell_regions);
 
         let mut elements = HashMap::new();
         for cell in grid.entity_types(grid.topology_dim()) {
@@ -361,6 +416,87 @@ impl<
     }
 }
 
+/// Gather the coefficients of a function in a distributed function space onto process 0
+///
+/// The input contains the coefficients of the local DOFs on each process. Returns the coefficients
+/// ordered by global DOF index on process 0, and None on all other processes. The grid of the space
+/// can be gathered with [crate::grid_tools::gather_grid_to_root], which only supports grids with a
+/// single cell type.
+pub fn gather_to_root<Space: FunctionSpaceTrait>(
+    space: &Space,
+    values: &[Space::T],
+) -> Option<Vec<Space::T>>
+where
+    Space::T: Equivalence,
+{
+    assert_eq!(values.len(), space.local_size());
+    let mut indices = vec![];
+    let mut owned_values = vec![];
+    for (i, v) in values.iter().enumerate() {
+        if space.ownership(i) == Ownership::Owned {
+            indices.push(space.global_dof_index(i));
+            owned_values.push(*v);
+        }
+    }
+
+    let comm = space.comm();
+    if comm.rank() == 0 {
+        let mut result = vec![Space::T::zero(); space.global_size()];
+        for (i, v) in indices.iter().zip(&owned_values) {
+            result[*i] = *v;
+        }
+        for p in 1..comm.size() {
+            let process = comm.process_at_rank(p);
+            let (indices, _status) = process.receive_vec::<usize>();
+            let (values, _status) = process.receive_vec::<Space::T>();
+            for (i, v) in indices.iter().zip(&values) {
+                result[*i] = *v;
+            }
+        }
+        Some(result)
+    } else {
+        mpi::request::scope(|scope| {
+            let process = comm.process_at_rank(0);
+            let _ = WaitGuard::from(process.immediate_send(scope, &indices));
+            let _ = WaitGuard::from(process.immediate_send(scope, &owned_values));
+        });
+        None
+    }
+}
+
+/// Create the matrix that maps the values of a function at the vertices of a volume mesh to the
+/// coefficients of its trace in a function space on the boundary of the mesh
+///
+/// `volume_vertices` gives the index in the volume mesh of the point with each id in the grid of
+/// the space, as returned by [crate::grid_tools::volume_boundary]. The matrix is sparse, with a
+/// single entry in each row, and has shape `[space.global_size(), nvolume_vertices]`. Its transpose maps coefficients in the boundary
+/// space to the vertices of the volume mesh. The space must be a serial degree 1 continuous
+/// Lagrange space, so that each DOF is associated with a vertex.
+pub fn trace_matrix<Space: FunctionSpaceTrait>(
+    space: &Space,
+    volume_vertices: &[usize],
+    nvolume_vertices: usize,
+) -> CsrMatrix<Space::T> {
+    if !space.is_serial() {
+        panic!("Trace matrices can only be created for function spaces stored in serial");
+    }
+    let grid = space.grid();
+    if space.global_size() != grid.entity_count(ReferenceCellType::Point) {
+        panic!("Trace matrices can only be created for spaces with one DOF at each vertex");
+    }
+    let mut rows = vec![];
+    let mut cols = vec![];
+    for vertex in grid.entity_iter(0) {
+        let volume_vertex = volume_vertices[vertex.id().unwrap()];
+        for dof in space.get_local_dof_numbers(0, vertex.local_index()) {
+            rows.push(space.global_dof_index(*dof));
+            cols.push(volume_vertex);
+        }
+    }
+    let data = vec![Space::T::one(); rows.len()];
+    CsrMatrix::from_aij([space.global_size(), nvolume_vertices], &rows, &cols, &data).unwrap()
+}
+
 /// Assign DOFs to entities.
 pub fn assign_dofs<
     T: RlstScalar + MatrixInverse,
@@ -373,6 +509,27 @@ pub fn assign_dofs<
         FiniteElement = CiarletElement<T>,
         CellType = ReferenceCellType,
     >,
+) -> (DofList, [DofList; 4], usize, OwnerData) {
+    assign_dofs_in_regions(rank, grid, e_family, None)
+}
+
+/// Assign DOFs to entities, with a separate set of DOFs for each region that an entity is part of
+///
+/// If `cell_regions` is `None`, all cells are in the same region. The DOFs of an entity are
+/// listed in the order in which its regions are first found, and the owner data of each DOF
+/// refers to its position in this list.
+pub fn assign_dofs_in_regions<
+    T: RlstScalar + MatrixInverse,
+    GridImpl: Grid<T = T::Real, EntityDescriptor = ReferenceCellType> + Sync,
+>(
+    rank: usize,
+    grid: &GridImpl,
+    e_family: &impl ElementFamily<
+        T = T,
+        FiniteElement = CiarletElement<T>,
+        CellType = ReferenceCellType,
+    >,
+    cell_regions: Option<&[usize]>,
 ) -> (DofList, [DofList; 4], usize, OwnerData) {
     let mut size = 0;
     let mut entity_dofs: [Vec<Vec<usize>>; 4] = [vec![], vec![], vec![], vec![]];
@@ -402,6 +559,8 @@ pub fn assign_dofs<
         entity_dofs[d] = vec![vec![]; entity_counts[d]];
     }
     let mut cell_dofs = vec![vec![]; entity_counts[tdim]];
+    // The DOFs of each (dimension, entity, region), when there is more than one region
+    let mut region_dofs: HashMap<(usize, usize, usize), Vec<usize>> = HashMap::new();
 
     let mut max_rank = rank;
     for cell in grid.entity_iter(tdim) {
@@ -421,6 +580,23 @@ pub fn assign_dofs<
             for (i, e) in topology.sub_entity_iter(d).enumerate() {
                 let e_dofs = element.entity_dofs(d, i).unwrap();
                 if !e_dofs.is_empty() {
+                    if let Some(regions) = cell_regions {
+                        let key = (d, e, regions[cell.local_index()]);
+                        let dofs = region_dofs.entry(key).or_insert_with(|| {
+                            let dofs = (size..size + e_dofs.len()).collect::<Vec<_>>();
+                            let offset = edofs_d[e].len();
+                            for dof_i in 0..e_dofs.len() {
+                                owner_data.push((rank, d, e, offset + dof_i));
+                            }
+                            size += e_dofs.len();
+                            edofs_d[e].extend_from_slice(&dofs);
+                            dofs
+                        });
+                        for (local_dof, dof) in e_dofs.iter().zip(dofs.iter()) {
+                            cell_dofs[cell.local_index()][*local_dof] = *dof;
+                        }
+                        continue;
+                    }
                     if edofs_d[e].is_empty() {
                         for (dof_i, _d) in e_dofs.iter().enumerate() {
                             edofs_d[e].push(size);
@@ -0,0 +1,239 @@
+//! Integrals of functions over surfaces
+//!
+//! The integrals are computed using the quadrature rules used for the non-singular part of boundary
+//! assembly with a given set of [BoundaryAssemblerOptions]. For a distributed space, each process
+//! integrates over the cells that it owns and the results are summed over all processes, so every
+//! process gets the integral over the whole surface.
+use super::FunctionSpaceTrait;
+use crate::boundary_assemblers::BoundaryAssemblerOptions;
+use mpi::traits::{Communicator, CommunicatorCollectives, Equivalence};
+use ndelement::quadrature::simplex_rule;
+use ndelement::traits::FiniteElement;
+use ndgrid::traits::{Entity, GeometryMap, Grid};
+use ndgrid::types::Ownership;
+use num::{One, Zero};
+use rlst::{
+    rlst_dynamic_array2, rlst_dynamic_array4, RandomAccessByRef, RawAccess, RawAccessMut,
+    RlstScalar,
+};
+use std::collections::HashSet;
+
+/// Monopole and dipole moments of a charge density
+#[derive(Debug, Clone)]
+pub struct MultipoleMoments<T: RlstScalar> {
+    /// Monopole moment (total charge)
+    pub monopole: T,
+    /// Dipole moment
+    pub dipole: [T; 3],
+}
+
+/// Integrate `N` quantities that depend on the value of a function, the position and the normal
+///
+/// If `cells` is `None`, all the owned cells are included. Otherwise, `cells` gives the local
+/// indices of the cells to include on the current process.
+fn integrate_quantities<Space: FunctionSpaceTrait, const N: usize>(
+    space: &Space,
+    coefficients: &[Space::T],
+    options: &BoundaryAssemblerOptions,
+    cells: Option<&[usize]>,
+    integrand: impl Fn(
+        Space::T,
+        &[<Space::T as RlstScalar>::Real],
+        &[<Space::T as RlstScalar>::Real],
+    ) -> [Space::T; N],
+) -> [Space::T; N]
+where
+    Space::T: Equivalence,
+{
+    assert_eq!(coefficients.len(), space.local_size());
+    let grid = space.grid();
+    assert_eq!(grid.geometry_dim(), 3);
+    assert_eq!(grid.topology_dim(), 2);
+
+    let cells = cells.map(|cells| cells.iter().copied().collect::<HashSet<_>>());
+    let mut result = [Space::T::zero(); N];
+
+    for cell_type in grid.entity_types(2) {
+        let npts = options.quadrature_degrees[cell_type];
+        let qrule = simplex_rule(*cell_type, npts).unwrap();
+        let mut qpoints = rlst_dynamic_array2!(<Space::T as RlstScalar>::Real, [2, npts]);
+        for (i, p) in qpoints.data_mut().iter_mut().enumerate() {
+            *p = num::cast::<f64, <Space::T as RlstScalar>::Real>(qrule.points[i]).unwrap();
+        }
+
+        let element = space.element(*cell_type);
+        let mut table = rlst_dynamic_array4!(Space::T, element.tabulate_array_shape(0, npts));
+        element.tabulate(&qpoints, 0, &mut table);
+
+        let evaluator = grid.geometry_map(*cell_type, qpoints.data());
+        let zero = <Space::T as RlstScalar>::Real::zero();
+        let mut points = vec![zero; 3 * npts];
+        let mut jacobians = vec![zero; 6 * npts];
+        let mut jdets = vec![zero; npts];
+        let mut normals = vec![zero; 3 * npts];
+
+        for cell in grid.entity_iter(2) {
+            if cell.entity_type() != *cell_type || cell.ownership() != Ownership::Owned {
+                continue;
+            }
+            let index = cell.local_index();
+            if let Some(cells) = &cells {
+                if !cells.contains(&index) {
+                    continue;
+                }
+            }
+            evaluator.points(index, &mut points);
+            evaluator.jacobians_dets_normals(index, &mut jacobians, &mut jdets, &mut normals);
+            let dofs = space.cell_dofs(index).unwrap();
+
+            for (p, w) in qrule.weights.iter().enumerate() {
+                let value = dofs
+                    .iter()
+                    .enumerate()
+                    .fold(Space::T::zero(), |v, (b, dof)| {
+                        v + coefficients[*dof] * *table.get([0, p, b, 0]).unwrap()
+                    });
+                let scale = Space::T::from_real(
+                    num::cast::<f64, <Space::T as RlstScalar>::Real>(*w).unwrap() * jdets[p],
+                );
+                for (r, q) in result.iter_mut().zip(integrand(
+                    value,
+                    &points[3 * p..3 * p + 3],
+                    &normals[3 * p..3 * p + 3],
+                )) {
+                    *r += q * scale;
+                }
+            }
+        }
+    }
+
+    // Sum the contributions of the cells owned by each process
+    let comm = space.comm();
+    let mut all_results = vec![Space::T::zero(); N * comm.size() as usize];
+    comm.all_gather_into(&result[..], &mut all_results[..]);
+    let mut total = [Space::T::zero(); N];
+    for process_result in all_results.chunks(N) {
+        for (t, r) in total.iter_mut().zip(process_result) {
+            *t += *r;
+        }
+    }
+    total
+}
+
+/// The integral of a function over the surface
+pub fn integral<Space: FunctionSpaceTrait>(
+    space: &Space,
+    coefficients: &[Space::T],
+    options: &BoundaryAssemblerOptions,
+) -> Space::T
+where
+    Space::T: Equivalence,
+{
+    integrate_quantities(space, coefficients, options, None, |u, _, _| [u])[0]
+}
+
+/// The integral of a function over a patch of cells
+///
+/// On each process, `cells` gives the local indices of the cells in the patch; only cells owned by
+/// the process are included. If the function is the normal derivative of a solution, this is the flux through the patch.
+pub fn patch_integral<Space: FunctionSpaceTrait>(
+    space: &Space,
+    coefficients: &[Space::T],
+    cells: &[usize],
+    options: &BoundaryAssemblerOptions,
+) -> Space::T
+where
+    Space::T: Equivalence,
+{
+    integrate_quantities(space, coefficients, options, Some(cells), |u, _, _| [u])[0]
+}
+
+/// The mean value of a function over the surface
+pub fn mean_value<Space: FunctionSpaceTrait>(
+    space: &Space,
+    coefficients: &[Space::T],
+    options: &BoundaryAssemblerOptions,
+) -> Space::T
+where
+    Space::T: Equivalence,
+{
+    let [value, area] = integrate_quantities(space, coefficients, options, None, |u, _, _| {
+        [u, Space::T::one()]
+    });
+    value / area
+}
+
+/// The first moments of a function: the integrals of the function multiplied by each coordinate
+pub fn first_moments<Space: FunctionSpaceTrait>(
+    space: &Space,
+    coefficients: &[Space::T],
+    options: &BoundaryAssemblerOptions,
+) -> [Space::T; 3]
+where
+    Space::T: Equivalence,
+{
+    integrate_quantities(space, coefficients, options, None, |u, x, _| {
+        [u.mul_real(x[0]), u.mul_real(x[1]), u.mul_real(x[2])]
+    })
+}
+
+/// The centre of charge of a function: its first moments divided by its integral
+pub fn centre_of_charge<Space: FunctionSpaceTrait>(
+    space: &Space,
+    coefficients: &[Space::T],
+    options: &BoundaryAssemblerOptions,
+) -> [Space::T; 3]
+where
+    Space::T: Equivalence,
+{
+    let [total, m0, m1, m2] =
+        integrate_quantities(space, coefficients, options, None, |u, x, _| {
+            [u, u.mul_real(x[0]), u.mul_real(x[1]), u.mul_real(x[2])]
+        });
+    [m0 / total, m1 / total, m2 / total]
+}
+
+/// The monopole and dipole moments of a surface charge density about a centre
+///
+/// These give the leading terms of the far-field expansion of the single layer potential of the
+/// density.
+pub fn multipole_moments<Space: FunctionSpaceTrait>(
+    space: &Space,
+    coefficients: &[Space::T],
+    centre: [<Space::T as RlstScalar>::Real; 3],
+    options: &BoundaryAssemblerOptions,
+) -> MultipoleMoments<Space::T>
+where
+    Space::T: Equivalence,
+{
+    let [monopole, d0, d1, d2] =
+        integrate_quantities(space, coefficients, options, None, |u, x, _| {
+            [
+                u,
+                u.mul_real(x[0] - centre[0]),
+                u.mul_real(x[1] - centre[1]),
+                u.mul_real(x[2] - centre[2]),
+            ]
+        });
+    MultipoleMoments {
+        monopole,
+        dipole: [d0, d1, d2],
+    }
+}
+
+/// The dipole moment of a surface dipole density: the integral of the density times the normal
+///
+/// This gives the leading term of the far-field expansion of the double layer potential of the
+/// density.
+pub fn normal_dipole_moment<Space: FunctionSpaceTrait>(
+    space: &Space,
+    coefficients: &[Space::T],
+    options: &BoundaryAssemblerOptions,
+) -> [Space::T; 3]
+where
+    Space::T: Equivalence,
+{
+    integrate_quantities(space, coefficients, options, None, |u, _, n| {
+        [u.mul_real(n[0]), u.mul_real(n[1]), u.mul_real(n[2])]
+    })
+}
@@ -0,0 +1,33 @@
+//! Grid utilities
+mod boundary;
+mod curvature;
+mod gather;
+mod healing;
+mod regions;
+mod volume_boundary;
+
+use ndelement::types::ReferenceCellType;
+
+pub use boundary::{boundary_edges, boundary_loops, edge_chains};
+pub use curvature::{
+    cell_areas, cell_curvatures, patch_area, surface_area, vertex_coordinates, vertex_curvatures,
+    Curvatures,
+};
+pub use gather::gather_grid_to_root;
+pub use healing::{heal_mesh, HealedMesh, HealingReport};
+pub use regions::regions_from_edges;
+pub use volume_boundary::{volume_boundary, VolumeBoundary};
+
+/// The vertices of a reference cell, as the two coordinates of each vertex in turn
+pub(crate) fn reference_vertices<T: num::NumCast>(cell_type: ReferenceCellType) -> Vec<T> {
+    match cell_type {
+        ReferenceCellType::Triangle => vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0],
+        ReferenceCellType::Quadrilateral => vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 1.0],
+        _ => {
+            panic!("Unsupported cell type: {cell_type:?}");
+        }
+    }
+    .iter()
+    .map(|x| num::cast::<f64, T>(*x).unwrap())
+    .collect()
+}
@@ -0,0 +1,107 @@
+//! Boundary of a surface grid
+
+use ndgrid::traits::{Entity, Grid, Topology};
+use ndgrid::types::Ownership;
+use std::collections::HashMap;
+
+/// Get the local indices of the boundary edges of a surface grid
+///
+/// An edge is on the boundary if it is connected to exactly one cell. For a grid distributed
+/// across processes, only edges of cells owned by the current process are considered, so the
+/// edges at the outside of the ghost layer are not reported.
+pub fn boundary_edges<G: Grid>(grid: &G) -> Vec<usize> {
+    grid.entity_iter(1)
+        .filter(|edge| {
+            let cells = edge.topology().connected_entity_iter(2).collect::<Vec<_>>();
+            cells.len() == 1 && grid.entity(2, cells[0]).unwrap().ownership() == Ownership::Owned
+        })
+        .map(|edge| edge.local_index())
+        .collect()
+}
+
+/// Get the boundary of a surface grid as loops of vertices
+///
+/// Each loop is a list of local vertex indices in which each vertex is joined to the next by a
+/// boundary edge, and the last vertex is joined to the first. If the boundary of the local part of
+/// a distributed grid is not closed, the chain of vertices is returned without the closing edge.
+pub fn boundary_loops<G: Grid>(grid: &G) -> Vec<Vec<usize>> {
+    let edges = boundary_edges(grid)
+        .iter()
+        .map(|e| {
+            let vertices = grid
+                .entity(1, *e)
+                .unwrap()
+                .topology()
+                .sub_entity_iter(0)
+                .collect::<Vec<_>>();
+            [vertices[0], vertices[1]]
+        })
+        .collect::<Vec<_>>();
+    edge_chains(&edges)
+}
+
+/// Join edges into chains of vertices
+///
+/// Each edge is given as a pair of vertex indices. Each chain is a list of vertices in which each
+/// vertex is joined to the next by an edge. A chain that ends at its first vertex is returned as a
+/// loop, without repeating the first vertex. Open chains are always walked from one end to the
+/// other, so the order of the edges does not affect how they are split into chains.
+pub fn edge_chains(edges: &[[usize; 2]]) -> Vec<Vec<usize>> {
+    let mut vertex_edges: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (i, e) in edges.iter().enumerate() {
+        for v in e {
+            vertex_edges.entry(*v).or_default().push(i);
+        }
+    }
+
+    let mut used = vec![false; edges.len()];
+    let mut chains = vec![];
+
+    // Open chains are started at a vertex that is only connected to one edge, so that they are
+    // not split into several chains when the walk starts part way along them
+    for (start, e) in edges.iter().enumerate() {
+        for v in e {
+            if vertex_edges[v].len() == 1 && !used[start] {
+                chains.push(walk_chain(edges, &vertex_edges, &mut used, start, *v));
+            }
+        }
+    }
+    // All remaining edges are in closed loops
+    for (start, e) in edges.iter().enumerate() {
+        if !used[start] {
+            chains.push(walk_chain(edges, &vertex_edges, &mut used, start, e[0]));
+        }
+    }
+    chains
+}
+
+/// Walk along unused edges, starting with the edge `start` at the vertex `first`
+fn walk_chain(
+    edges: &[[usize; 2]],
+    vertex_edges: &HashMap<usize, Vec<usize>>,
+    used: &mut [bool],
+    start: usize,
+    first: usize,
+) -> Vec<usize> {
+    used[start] = true;
+    let mut current = if edges[start][0] == first {
+        edges[start][1]
+    } else {
+        edges[start][0]
+    };
+    let mut vertices = vec![first];
+    while current != first {
+        vertices.push(current);
+        if let Some(next) = vertex_edges[&current].iter().find(|e| !used[**e]) {
+            used[*next] = true;
+            current = if edges[*next][0] == current {
+                edges[*next][1]
+            } else {
+                edges[*next][0]
+            };
+        } else {
+            break;
+        }
+    }
+    vertices
+}
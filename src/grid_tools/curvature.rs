@@ -0,0 +1,235 @@
+//! Curvature and area of surface grids
+
+use super::{boundary_edges, reference_vertices};
+use ndelement::types::ReferenceCellType;
+use ndgrid::traits::{Entity, GeometryMap, Grid, Topology};
+use ndgrid::types::RealScalar;
+use num::Float;
+use std::collections::HashMap;
+
+/// Discrete curvatures of a surface grid
+pub struct Curvatures<T: RealScalar> {
+    /// Mean curvature
+    pub mean: Vec<T>,
+    /// Gaussian curvature
+    pub gaussian: Vec<T>,
+}
+
+fn sub<T: RealScalar>(a: &[T; 3], b: &[T; 3]) -> [T; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn dot<T: RealScalar>(a: &[T; 3], b: &[T; 3]) -> T {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross<T: RealScalar>(a: &[T; 3], b: &[T; 3]) -> [T; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn triangle_area<T: RealScalar>(a: &[T; 3], b: &[T; 3], c: &[T; 3]) -> T {
+    let n = cross(&sub(b, a), &sub(c, a));
+    Float::sqrt(dot(&n, &n)) / num::cast::<f64, T>(2.0).unwrap()
+}
+
+fn cell_count<G: Grid>(grid: &G) -> usize {
+    grid.entity_types(2)
+        .iter()
+        .map(|&i| grid.entity_count(i))
+        .sum::<usize>()
+}
+
+/// Get the coordinates of the vertices of a grid, indexed by local vertex index
+pub fn vertex_coordinates<T: RealScalar, G: Grid<T = T, EntityDescriptor = ReferenceCellType>>(
+    grid: &G,
+) -> Vec<[T; 3]> {
+    assert_eq!(grid.geometry_dim(), 3);
+    assert_eq!(grid.topology_dim(), 2);
+
+    let zero = num::cast::<f64, T>(0.0).unwrap();
+    let reference_points = grid
+        .entity_types(2)
+        .iter()
+        .map(|cell_type| (*cell_type, reference_vertices::<T>(*cell_type)))
+        .collect::<HashMap<_, _>>();
+    let evaluators = grid
+        .entity_types(2)
+        .iter()
+        .map(|cell_type| {
+            (
+                *cell_type,
+                grid.geometry_map(*cell_type, &reference_points[cell_type]),
+            )
+        })
+        .collect::<HashMap<_, _>>();
+
+    let mut coordinates = vec![[zero; 3]; grid.entity_count(ReferenceCellType::Point)];
+    let mut points = vec![];
+    for cell in grid.entity_iter(2) {
+        let cell_type = cell.entity_type();
+        points.resize(3 * reference_points[&cell_type].len() / 2, zero);
+        evaluators[&cell_type].points(cell.local_index(), &mut points);
+        for (i, v) in cell.topology().sub_entity_iter(0).enumerate() {
+            coordinates[v] = [points[3 * i], points[3 * i + 1], points[3 * i + 2]];
+        }
+    }
+    coordinates
+}
+
+/// Get the area of each cell, indexed by local cell index
+///
+/// The areas are computed from the positions of the vertices of each cell, so they are exact for
+/// flat cells. Quadrilaterals are split into two triangles.
+pub fn cell_areas<T: RealScalar, G: Grid<T = T, EntityDescriptor = ReferenceCellType>>(
+    grid: &G,
+) -> Vec<T> {
+    let coordinates = vertex_coordinates(grid);
+    let mut areas = vec![num::cast::<f64, T>(0.0).unwrap(); cell_count(grid)];
+    for cell in grid.entity_iter(2) {
+        let v = cell
+            .topology()
+            .sub_entity_iter(0)
+            .map(|i| &coordinates[i])
+            .collect::<Vec<_>>();
+        areas[cell.local_index()] = match cell.entity_type() {
+            ReferenceCellType::Triangle => triangle_area(v[0], v[1], v[2]),
+            ReferenceCellType::Quadrilateral => {
+                triangle_area(v[0], v[1], v[3]) + triangle_area(v[0], v[3], v[2])
+            }
+            cell_type => {
+                panic!("Unsupported cell type: {cell_type:?}");
+            }
+        };
+    }
+    areas
+}
+
+/// Get the total area of the cells in a patch
+pub fn patch_area<T: RealScalar, G: Grid<T = T, EntityDescriptor = ReferenceCellType>>(
+    grid: &G,
+    cells: &[usize],
+) -> T {
+    let areas = cell_areas(grid);
+    cells
+        .iter()
+        .fold(num::cast::<f64, T>(0.0).unwrap(), |a, c| a + areas[*c])
+}
+
+/// Get the total area of a surface grid
+pub fn surface_area<T: RealScalar, G: Grid<T = T, EntityDescriptor = ReferenceCellType>>(
+    grid: &G,
+) -> T {
+    cell_areas(grid)
+        .iter()
+        .fold(num::cast::<f64, T>(0.0).unwrap(), |a, b| a + *b)
+}
+
+/// Estimate the mean and Gaussian curvature at each vertex of a triangle grid
+///
+/// The Gaussian curvature is computed from the angle deficit at each vertex and the mean curvature
+/// from the cotangent Laplacian of the vertex positions, with each vertex assigned a third of the
+/// area of each adjacent cell. The mean curvature is positive where the surface bends away from
+/// its normal, so it is close to 1 everywhere on a refined unit sphere with outward normals. At
+/// boundary vertices, the Gaussian curvature includes the turning of the boundary and the mean
+/// curvature is not reliable.
+pub fn vertex_curvatures<T: RealScalar, G: Grid<T = T, EntityDescriptor = ReferenceCellType>>(
+    grid: &G,
+) -> Curvatures<T> {
+    let zero = num::cast::<f64, T>(0.0).unwrap();
+    let three = num::cast::<f64, T>(3.0).unwrap();
+    let four = num::cast::<f64, T>(4.0).unwrap();
+    let pi = num::cast::<f64, T>(std::f64::consts::PI).unwrap();
+
+    let coordinates = vertex_coordinates(grid);
+    let nvertices = coordinates.len();
+    let mut vertex_areas = vec![zero; nvertices];
+    let mut angle_sums = vec![zero; nvertices];
+    let mut laplacians = vec![[zero; 3]; nvertices];
+    let mut normals = vec![[zero; 3]; nvertices];
+
+    for cell in grid.entity_iter(2) {
+        if cell.entity_type() != ReferenceCellType::Triangle {
+            panic!(
+                "Curvature can only be computed for triangle grids, found cell type {:?}",
+                cell.entity_type()
+            );
+        }
+        let v = cell.topology().sub_entity_iter(0).collect::<Vec<_>>();
+        let n = cross(
+            &sub(&coordinates[v[1]], &coordinates[v[0]]),
+            &sub(&coordinates[v[2]], &coordinates[v[0]]),
+        );
+        let area = Float::sqrt(dot(&n, &n)) / num::cast::<f64, T>(2.0).unwrap();
+        for i in 0..3 {
+            let a = v[i];
+            let b = v[(i + 1) % 3];
+            let c = v[(i + 2) % 3];
+            let e0 = sub(&coordinates[b], &coordinates[a]);
+            let e1 = sub(&coordinates[c], &coordinates[a]);
+            let cos = dot(&e0, &e1);
+            let sin = Float::sqrt(dot(&cross(&e0, &e1), &cross(&e0, &e1)));
+
+            vertex_areas[a] += area / three;
+            angle_sums[a] += Float::atan2(sin, cos);
+            for (nj, n_j) in normals[a].iter_mut().zip(&n) {
+                *nj += *n_j;
+            }
+
+            // The angle at a is opposite the edge from b to c
+            let cot = cos / sin;
+            let bc = sub(&coordinates[b], &coordinates[c]);
+            for (j, bc_j) in bc.iter().enumerate() {
+                laplacians[b][j] += cot * *bc_j;
+                laplacians[c][j] -= cot * *bc_j;
+            }
+        }
+    }
+
+    let mut is_boundary = vec![false; nvertices];
+    for e in boundary_edges(grid) {
+        for v in grid.entity(1, e).unwrap().topology().sub_entity_iter(0) {
+            is_boundary[v] = true;
+        }
+    }
+
+    let mut mean = vec![zero; nvertices];
+    let mut gaussian = vec![zero; nvertices];
+    for (v, area) in vertex_areas.iter().enumerate() {
+        if *area == zero {
+            continue;
+        }
+        let full_angle = if is_boundary[v] { pi } else { pi + pi };
+        gaussian[v] = (full_angle - angle_sums[v]) / *area;
+        let normal_size = Float::sqrt(dot(&normals[v], &normals[v]));
+        mean[v] = dot(&laplacians[v], &normals[v]) / normal_size / (four * *area);
+    }
+
+    Curvatures { mean, gaussian }
+}
+
+/// Estimate the mean and Gaussian curvature on each cell of a triangle grid
+///
+/// The value on each cell is the average of the values at its vertices computed by
+/// [vertex_curvatures].
+pub fn cell_curvatures<T: RealScalar, G: Grid<T = T, EntityDescriptor = ReferenceCellType>>(
+    grid: &G,
+) -> Curvatures<T> {
+    let vertex_values = vertex_curvatures(grid);
+    let zero = num::cast::<f64, T>(0.0).unwrap();
+    let three = num::cast::<f64, T>(3.0).unwrap();
+
+    let mut mean = vec![zero; cell_count(grid)];
+    let mut gaussian = vec![zero; cell_count(grid)];
+    for cell in grid.entity_iter(2) {
+        for v in cell.topology().sub_entity_iter(0) {
+            mean[cell.local_index()] += vertex_values.mean[v] / three;
+            gaussian[cell.local_index()] += vertex_values.gaussian[v] / three;
+        }
+    }
+
+    Curvatures { mean, gaussian }
+}
@@ -0,0 +1,119 @@
+//! Gathering distributed grids
+
+use super::vertex_coordinates;
+use mpi::{
+    point_to_point::{Destination, Source},
+    request::WaitGuard,
+    topology::Communicator,
+    traits::Equivalence,
+};
+use ndelement::{ciarlet::CiarletElement, reference_cell, types::ReferenceCellType};
+use ndgrid::{
+    traits::{Builder, Entity, Grid, ParallelGrid, Topology},
+    types::{Ownership, RealScalar},
+    SingleElementGrid, SingleElementGridBuilder,
+};
+use std::collections::HashMap;
+
+/// Gather a distributed grid onto process 0
+///
+/// Each process sends the cells it owns to process 0, which builds a serial grid from them. The
+/// points and cells of the serial grid have the same ids as in the distributed grid. The geometry
+/// is rebuilt from the vertices of each cell, so the serial grid has a degree 1 geometry.
+///
+/// Only grids with a single cell type can currently be gathered: this panics if the grid contains
+/// more than one cell type.
+///
+/// Returns the serial grid on process 0 and None on all other processes.
+pub fn gather_grid_to_root<
+    T: RealScalar + Equivalence,
+    C: Communicator,
+    G: ParallelGrid<C> + Grid<T = T, EntityDescriptor = ReferenceCellType>,
+>(
+    grid: &G,
+) -> Option<SingleElementGrid<T, CiarletElement<T>>> {
+    let cell_types = grid.entity_types(2);
+    if cell_types.len() != 1 {
+        panic!(
+            "Only grids with a single cell type can be gathered, found cell types {cell_types:?}"
+        );
+    }
+    let cell_type = cell_types[0];
+    let coordinates = vertex_coordinates(grid);
+
+    let mut vertex_ids = vec![];
+    let mut vertex_coords = vec![];
+    let mut cell_ids = vec![];
+    let mut cell_vertices = vec![];
+    let mut included = vec![false; coordinates.len()];
+    for cell in grid.entity_iter(2) {
+        if cell.ownership() == Ownership::Owned {
+            cell_ids.push(cell.id().unwrap());
+            for v in cell.topology().sub_entity_iter(0) {
+                let id = grid.entity(0, v).unwrap().id().unwrap();
+                cell_vertices.push(id);
+                if !included[v] {
+                    included[v] = true;
+                    vertex_ids.push(id);
+                    vertex_coords.extend_from_slice(&coordinates[v]);
+                }
+            }
+        }
+    }
+
+    let comm = grid.comm();
+    if comm.rank() == 0 {
+        let mut points = HashMap::new();
+        let mut cells = HashMap::new();
+        let nvertices = reference_cell::entity_counts(cell_type)[0];
+
+        let mut add_data = |vertex_ids: &[usize],
+                            vertex_coords: &[T],
+                            cell_ids: &[usize],
+                            cell_vertices: &[usize]| {
+            for (id, pt) in vertex_ids.iter().zip(vertex_coords.chunks(3)) {
+                points.insert(*id, pt.to_vec());
+            }
+            for (id, vs) in cell_ids.iter().zip(cell_vertices.chunks(nvertices)) {
+                cells.insert(*id, vs.to_vec());
+            }
+        };
+
+        add_data(&vertex_ids, &vertex_coords, &cell_ids, &cell_vertices);
+        for p in 1..comm.size() {
+            let process = comm.process_at_rank(p);
+            let (vertex_ids, _status) = process.receive_vec::<usize>();
+            let (vertex_coords, _status) = process.receive_vec::<T>();
+            let (cell_ids, _status) = process.receive_vec::<usize>();
+            let (cell_vertices, _status) = process.receive_vec::<usize>();
+            add_data(&vertex_ids, &vertex_coords, &cell_ids, &cell_vertices);
+        }
+
+        let mut b = SingleElementGridBuilder::new_with_capacity(
+            3,
+            points.len(),
+            cells.len(),
+            (cell_type, 1),
+        );
+        let mut point_ids = points.keys().copied().collect::<Vec<_>>();
+        point_ids.sort();
+        for id in point_ids {
+            b.add_point(id, &points[&id]);
+        }
+        let mut cell_ids = cells.keys().copied().collect::<Vec<_>>();
+        cell_ids.sort();
+        for id in cell_ids {
+            b.add_cell(id, &cells[&id]);
+        }
+        Some(b.create_grid())
+    } else {
+        mpi::request::scope(|scope| {
+            let process = comm.process_at_rank(0);
+            let _ = WaitGuard::from(process.immediate_send(scope, &vertex_ids));
+            let _ = WaitGuard::from(process.immediate_send(scope, &vertex_coords));
+            let _ = WaitGuard::from(process.immediate_send(scope, &cell_ids));
+            let _ = WaitGuard::from(process.immediate_send(scope, &cell_vertices));
+        });
+        None
+    }
+}
@@ -0,0 +1,271 @@
+//! Cleaning up imported triangle meshes
+
+use ndelement::{ciarlet::CiarletElement, types::ReferenceCellType};
+use ndgrid::{traits::Builder, types::RealScalar, SingleElementGrid, SingleElementGridBuilder};
+use num::Float;
+use std::collections::{HashMap, HashSet};
+
+/// A summary of the changes made when healing a mesh
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HealingReport {
+    /// Number of vertices that were merged into a coincident vertex
+    pub merged_vertices: usize,
+    /// Number of edges shorter than the minimum edge length that were collapsed
+    pub collapsed_edges: usize,
+    /// Number of degenerate cells that were removed
+    pub removed_degenerate_cells: usize,
+    /// Number of duplicate cells that were removed
+    pub removed_duplicate_cells: usize,
+    /// Number of vertices that were removed because no cell uses them
+    pub removed_unused_vertices: usize,
+}
+
+/// A healed triangle mesh
+#[derive(Debug, Clone)]
+pub struct HealedMesh<T: RealScalar> {
+    /// Coordinates of the points, with three values for each point
+    pub points: Vec<T>,
+    /// Vertices of the cells, with three values for each cell
+    pub cells: Vec<usize>,
+    /// The changes that were made
+    pub report: HealingReport,
+}
+
+impl<T: RealScalar> HealedMesh<T> {
+    /// Create a serial grid from the healed mesh
+    pub fn create_grid(&self) -> SingleElementGrid<T, CiarletElement<T>> {
+        let mut b = SingleElementGridBuilder::new_with_capacity(
+            3,
+            self.points.len() / 3,
+            self.cells.len() / 3,
+            (ReferenceCellType::Triangle, 1),
+        );
+        for (i, p) in self.points.chunks(3).enumerate() {
+            b.add_point(i, p);
+        }
+        for (i, c) in self.cells.chunks(3).enumerate() {
+            b.add_cell(i, c);
+        }
+        b.create_grid()
+    }
+}
+
+/// Find the representative of a vertex, compressing the path to it
+fn find(parent: &mut [usize], v: usize) -> usize {
+    let mut root = v;
+    while parent[root] != root {
+        root = parent[root];
+    }
+    let mut v = v;
+    while parent[v] != root {
+        let next = parent[v];
+        parent[v] = root;
+        v = next;
+    }
+    root
+}
+
+/// Merge two vertices, keeping the one with the lower index as the representative
+fn union(parent: &mut [usize], a: usize, b: usize) -> bool {
+    let a = find(parent, a);
+    let b = find(parent, b);
+    if a == b {
+        false
+    } else {
+        parent[a.max(b)] = a.min(b);
+        true
+    }
+}
+
+/// Find the candidate closest to the point `p`, if it is within `tolerance` of `p`
+fn closest_within<T: RealScalar>(
+    points: &[T],
+    p: usize,
+    candidates: impl Iterator<Item = usize>,
+    tolerance: T,
+) -> Option<usize> {
+    candidates
+        .map(|q| (q, distance(points, p, q)))
+        .filter(|(_, d)| *d <= tolerance)
+        .fold(None, |closest: Option<(usize, T)>, (q, d)| match closest {
+            Some((_, closest_d)) if closest_d <= d => closest,
+            _ => Some((q, d)),
+        })
+        .map(|(q, _)| q)
+}
+
+/// Merge each vertex into the closest earlier representative vertex within `tolerance`, using a
+/// background grid with spacing equal to the tolerance to find nearby representatives
+///
+/// Returns the number of merged vertices, or `None` (without merging any vertices) if the
+/// coordinates divided by the tolerance are too large to index the background grid.
+fn merge_vertices_with_grid<T: RealScalar>(
+    points: &[T],
+    tolerance: T,
+    parent: &mut [usize],
+) -> Option<usize> {
+    let grid_cells = points
+        .chunks(3)
+        .map(
+            |p| match [0, 1, 2].map(|i| num::cast::<T, i64>(Float::floor(p[i] / tolerance))) {
+                [Some(x), Some(y), Some(z)] => Some([x, y, z]),
+                _ => None,
+            },
+        )
+        .collect::<Option<Vec<_>>>()?;
+
+    let mut merged = 0;
+    let mut representatives = HashMap::<[i64; 3], Vec<usize>>::new();
+    for (p, c) in grid_cells.iter().enumerate() {
+        let candidates = (-1..=1)
+            .flat_map(|dx| (-1..=1).flat_map(move |dy| (-1..=1).map(move |dz| [dx, dy, dz])))
+            .filter_map(|[dx, dy, dz]| {
+                representatives.get(&[
+                    c[0].wrapping_add(dx),
+                    c[1].wrapping_add(dy),
+                    c[2].wrapping_add(dz),
+                ])
+            })
+            .flatten()
+            .copied();
+        if let Some(r) = closest_within(points, p, candidates, tolerance) {
+            parent[p] = r;
+            merged += 1;
+        } else {
+            representatives.entry(*c).or_default().push(p);
+        }
+    }
+    Some(merged)
+}
+
+/// Merge each vertex into the closest earlier representative vertex within `tolerance`, using a
+/// list of representatives sorted by their first coordinate to find nearby representatives
+///
+/// This is slower than [merge_vertices_with_grid], but works for any tolerance. Returns the number
+/// of merged vertices.
+fn merge_vertices_with_sort<T: RealScalar>(
+    points: &[T],
+    tolerance: T,
+    parent: &mut [usize],
+) -> usize {
+    let mut merged = 0;
+    let mut representatives: Vec<usize> = vec![];
+    for p in 0..points.len() / 3 {
+        let x = points[3 * p];
+        let start = representatives.partition_point(|r| points[3 * r] < x - tolerance);
+        let end = representatives.partition_point(|r| points[3 * r] <= x + tolerance);
+        let candidates = representatives[start..end].iter().copied();
+        if let Some(r) = closest_within(points, p, candidates, tolerance) {
+            parent[p] = r;
+            merged += 1;
+        } else {
+            let position = representatives.partition_point(|r| points[3 * r] < x);
+            representatives.insert(position, p);
+        }
+    }
+    merged
+}
+
+fn distance<T: RealScalar>(points: &[T], a: usize, b: usize) -> T {
+    Float::sqrt(
+        (0..3)
+            .map(|i| points[3 * a + i] - points[3 * b + i])
+            .fold(num::cast::<f64, T>(0.0).unwrap(), |s, d| s + d * d),
+    )
+}
+
+fn triangle_area<T: RealScalar>(points: &[T], v: &[usize]) -> T {
+    let a = [0, 1, 2].map(|i| points[3 * v[1] + i] - points[3 * v[0] + i]);
+    let b = [0, 1, 2].map(|i| points[3 * v[2] + i] - points[3 * v[0] + i]);
+    let n = [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ];
+    Float::sqrt(n[0] * n[0] + n[1] * n[1] + n[2] * n[2]) / num::cast::<f64, T>(2.0).unwrap()
+}
+
+/// Heal a triangle mesh
+///
+/// `points` contains the coordinates of the points (three values for each point) and `cells`
+/// contains the vertices of each triangle (three values for each cell). The following changes are
+/// made, in order:
+///
+/// 1. Each vertex is merged into the closest earlier vertex within `tolerance` that has not itself
+///    been merged. Merging is not transitive: a vertex is only merged if it is within `tolerance`
+///    of the vertex that it is merged into, so a chain of vertices that are each closer than
+///    `tolerance` to the next does not collapse into a single vertex.
+/// 2. Edges shorter than `min_edge_length` are collapsed by merging their two vertices. This is
+///    applied to each edge in turn, so a chain of short edges can collapse to a single vertex.
+/// 3. Cells with repeated vertices or with area at most `tolerance` squared are removed.
+/// 4. Cells with the same vertices as an earlier cell are removed.
+/// 5. Vertices that are not used by any cell are removed and the remaining vertices renumbered.
+///
+/// Merged vertices keep the position of the vertex with the lowest index.
+pub fn heal_mesh<T: RealScalar>(
+    points: &[T],
+    cells: &[usize],
+    tolerance: T,
+    min_edge_length: T,
+) -> HealedMesh<T> {
+    assert_eq!(points.len() % 3, 0);
+    assert_eq!(cells.len() % 3, 0);
+    let npoints = points.len() / 3;
+    let mut report = HealingReport::default();
+    let mut parent = (0..npoints).collect::<Vec<_>>();
+
+    // Merge coincident vertices
+    if tolerance > num::cast::<f64, T>(0.0).unwrap() {
+        report.merged_vertices = merge_vertices_with_grid(points, tolerance, &mut parent)
+            .unwrap_or_else(|| merge_vertices_with_sort(points, tolerance, &mut parent));
+    }
+
+    // Collapse short edges
+    for c in cells.chunks(3) {
+        for (i, j) in [(0, 1), (1, 2), (2, 0)] {
+            let a = find(&mut parent, c[i]);
+            let b = find(&mut parent, c[j]);
+            if a != b && distance(points, a, b) < min_edge_length && union(&mut parent, a, b) {
+                report.collapsed_edges += 1;
+            }
+        }
+    }
+
+    // Remove degenerate and duplicate cells
+    let min_area = tolerance * tolerance;
+    let mut new_cells = vec![];
+    let mut seen = HashSet::new();
+    for c in cells.chunks(3) {
+        let v = c.iter().map(|v| find(&mut parent, *v)).collect::<Vec<_>>();
+        if v[0] == v[1] || v[1] == v[2] || v[2] == v[0] || triangle_area(points, &v) <= min_area {
+            report.removed_degenerate_cells += 1;
+            continue;
+        }
+        let mut key = [v[0], v[1], v[2]];
+        key.sort();
+        if !seen.insert(key) {
+            report.removed_duplicate_cells += 1;
+            continue;
+        }
+        new_cells.extend_from_slice(&v);
+    }
+
+    // Remove unused vertices and renumber
+    let mut new_index = vec![None; npoints];
+    let mut new_points = vec![];
+    for v in new_cells.iter_mut() {
+        let old = *v;
+        *v = *new_index[old].get_or_insert_with(|| {
+            new_points.extend_from_slice(&points[3 * old..3 * old + 3]);
+            new_points.len() / 3 - 1
+        });
+    }
+    report.removed_unused_vertices =
+        npoints - report.merged_vertices - report.collapsed_edges - new_points.len() / 3;
+
+    HealedMesh {
+        points: new_points,
+        cells: new_cells,
+        report,
+    }
+}
@@ -0,0 +1,43 @@
+//! Regions of a surface grid
+
+use ndgrid::traits::{Entity, Grid, Topology};
+use std::collections::HashSet;
+
+/// Split the cells of a surface grid into regions separated by a set of edges
+///
+/// Two cells are in the same region if they can be joined by a path of cells in which each cell
+/// shares an edge that is not in `interface_edges` with the next. The regions are numbered from 0
+/// in order of their lowest cell index. Returns the region of each cell.
+pub fn regions_from_edges<G: Grid>(grid: &G, interface_edges: &[usize]) -> Vec<usize> {
+    let interface_edges = interface_edges.iter().copied().collect::<HashSet<_>>();
+    let ncells = grid.entity_iter(2).count();
+    let mut regions = vec![None; ncells];
+    let mut nregions = 0;
+    for start in 0..ncells {
+        if regions[start].is_some() {
+            continue;
+        }
+        regions[start] = Some(nregions);
+        let mut stack = vec![start];
+        while let Some(cell) = stack.pop() {
+            for edge in grid.entity(2, cell).unwrap().topology().sub_entity_iter(1) {
+                if interface_edges.contains(&edge) {
+                    continue;
+                }
+                for neighbour in grid
+                    .entity(1, edge)
+                    .unwrap()
+                    .topology()
+                    .connected_entity_iter(2)
+                {
+                    if regions[neighbour].is_none() {
+                        regions[neighbour] = Some(nregions);
+                        stack.push(neighbour);
+                    }
+                }
+            }
+        }
+        nregions += 1;
+    }
+    regions.iter().map(|r| r.unwrap()).collect()
+}
@@ -0,0 +1,110 @@
+//! Boundary surfaces of volume meshes
+
+use ndelement::{ciarlet::CiarletElement, types::ReferenceCellType};
+use ndgrid::{traits::Builder, types::RealScalar, SingleElementGrid, SingleElementGridBuilder};
+use std::collections::HashMap;
+
+/// The boundary surface of a tetrahedral volume mesh
+#[derive(Debug, Clone)]
+pub struct VolumeBoundary<T: RealScalar> {
+    /// Coordinates of the points of the surface, with three values for each point
+    pub points: Vec<T>,
+    /// Vertices of the triangles of the surface, with three values for each cell
+    pub cells: Vec<usize>,
+    /// The index in the volume mesh of each point of the surface
+    pub volume_vertices: Vec<usize>,
+}
+
+impl<T: RealScalar> VolumeBoundary<T> {
+    /// Create a serial grid from the boundary surface
+    ///
+    /// The id of each point in the grid is its index in `points`.
+    pub fn create_grid(&self) -> SingleElementGrid<T, CiarletElement<T>> {
+        let mut b = SingleElementGridBuilder::new_with_capacity(
+            3,
+            self.points.len() / 3,
+            self.cells.len() / 3,
+            (ReferenceCellType::Triangle, 1),
+        );
+        for (i, p) in self.points.chunks(3).enumerate() {
+            b.add_point(i, p);
+        }
+        for (i, c) in self.cells.chunks(3).enumerate() {
+            b.add_cell(i, c);
+        }
+        b.create_grid()
+    }
+}
+
+/// Get the boundary surface of a tetrahedral volume mesh
+///
+/// `points` contains the coordinates of the points (three values for each point) and `cells`
+/// contains the vertices of each tetrahedron (four values for each cell). A face is on the
+/// boundary if it is a face of exactly one tetrahedron. The triangles of the surface are oriented
+/// so that their normals point out of the volume.
+pub fn volume_boundary<T: RealScalar>(points: &[T], cells: &[usize]) -> VolumeBoundary<T> {
+    assert_eq!(points.len() % 3, 0);
+    assert_eq!(cells.len() % 4, 0);
+
+    let mut faces = HashMap::<[usize; 3], Option<[usize; 3]>>::new();
+    for c in cells.chunks(4) {
+        for opposite in 0..4 {
+            let others = (0..4)
+                .filter(|i| *i != opposite)
+                .map(|i| c[i])
+                .collect::<Vec<_>>();
+            let face = [others[0], others[1], others[2]];
+            let mut key = face;
+            key.sort();
+            faces
+                .entry(key)
+                .and_modify(|f| *f = None)
+                .or_insert_with(|| Some(oriented_face(points, face, c[opposite])));
+        }
+    }
+
+    let mut keys = faces
+        .iter()
+        .filter_map(|(key, face)| face.map(|_| *key))
+        .collect::<Vec<_>>();
+    keys.sort();
+
+    let mut surface_index = HashMap::new();
+    let mut surface_points = vec![];
+    let mut volume_vertices = vec![];
+    let mut surface_cells = vec![];
+    for key in keys {
+        for v in faces[&key].unwrap() {
+            let index = *surface_index.entry(v).or_insert_with(|| {
+                surface_points.extend_from_slice(&points[3 * v..3 * v + 3]);
+                volume_vertices.push(v);
+                volume_vertices.len() - 1
+            });
+            surface_cells.push(index);
+        }
+    }
+
+    VolumeBoundary {
+        points: surface_points,
+        cells: surface_cells,
+        volume_vertices,
+    }
+}
+
+/// Order the vertices of a face so that its normal points away from the opposite vertex
+fn oriented_face<T: RealScalar>(points: &[T], face: [usize; 3], opposite: usize) -> [usize; 3] {
+    let d = |a: usize, b: usize| [0, 1, 2].map(|i| points[3 * b + i] - points[3 * a + i]);
+    let a = d(face[0], face[1]);
+    let b = d(face[0], face[2]);
+    let c = d(face[0], opposite);
+    let n = [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ];
+    if n[0] * c[0] + n[1] * c[1] + n[2] * c[2] > num::cast::<f64, T>(0.0).unwrap() {
+        [face[0], face[2], face[1]]
+    } else {
+        face
+    }
+}
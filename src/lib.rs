@@ -3,15 +3,24 @@
 #![warn(missing_docs)]
 
 //pub mod bindings;
+pub mod analytic;
 pub mod boundary_assemblers;
+pub mod config;
+pub mod fingerprint;
 pub mod function;
+pub mod grid_tools;
 pub mod helmholtz;
 pub mod laplace;
+pub mod norm_estimation;
+pub mod prelude;
 pub mod shapes;
+pub mod solvers;
+pub mod units;
 
 #[cfg(test)]
 mod test {
     use approx as _;
     use cauchy as _;
     use criterion as _; // Hack to show that criterion is used, as cargo test does not see benches
+    use serde_json as _;
 }
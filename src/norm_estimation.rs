@@ -0,0 +1,154 @@
+//! Estimation of norms and condition numbers of operators
+//!
+//! The estimators in this module only need to be able to apply an operator (and its adjoint) to a
+//! vector, so they can be used for dense matrices as well as for operators that are only available
+//! as a matrix-vector product.
+use num::Zero;
+use rlst::{DynamicArray, RawAccess, RlstScalar, Shape};
+
+/// A pseudo-random starting vector
+///
+/// A fixed seed is used so that estimates are reproducible.
+fn start_vector<T: RlstScalar>(size: usize) -> Vec<T> {
+    let mut state = 0x2545f4914f6cdd1d_u64;
+    (0..size)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            T::from_real(
+                num::cast::<f64, T::Real>((state >> 11) as f64 / (1_u64 << 53) as f64 + 0.5)
+                    .unwrap(),
+            )
+        })
+        .collect()
+}
+
+/// The 2-norm of a vector
+fn vector_norm<T: RlstScalar>(v: &[T]) -> T::Real {
+    v.iter()
+        .fold(T::Real::zero(), |a, b| a + b.abs() * b.abs())
+        .sqrt()
+}
+
+/// Divide a vector by its 2-norm, returning the norm
+fn normalise<T: RlstScalar>(v: &mut [T]) -> T::Real {
+    let norm = vector_norm(v);
+    if norm > T::Real::zero() {
+        for i in v.iter_mut() {
+            *i = i.div_real(norm);
+        }
+    }
+    norm
+}
+
+/// Estimate the 2-norm of an operator using power iteration
+///
+/// `apply(x, y)` must set `y` to the product of the operator and `x`, and `apply_adjoint(y, x)`
+/// must set `x` to the product of the adjoint (conjugate transpose) of the operator and `y`. The
+/// iteration stops when the relative change in the estimate is below `tolerance` or after
+/// `max_iterations` iterations. The estimate approaches the largest singular value from below.
+pub fn estimate_norm<T: RlstScalar>(
+    shape: [usize; 2],
+    apply: impl Fn(&[T], &mut [T]),
+    apply_adjoint: impl Fn(&[T], &mut [T]),
+    max_iterations: usize,
+    tolerance: T::Real,
+) -> T::Real {
+    let mut x = start_vector::<T>(shape[1]);
+    let mut y = vec![T::zero(); shape[0]];
+    normalise(&mut x);
+
+    let mut estimate = T::Real::zero();
+    for _ in 0..max_iterations {
+        apply(&x, &mut y);
+        let new_estimate = vector_norm(&y);
+        if new_estimate == T::Real::zero() {
+            return new_estimate;
+        }
+        apply_adjoint(&y, &mut x);
+        normalise(&mut x);
+
+        let converged = (new_estimate - estimate).abs() <= tolerance * new_estimate;
+        estimate = new_estimate;
+        if converged {
+            break;
+        }
+    }
+    estimate
+}
+
+/// Estimate the 2-norm condition number of a square operator
+///
+/// The condition number is estimated as the product of the estimated norms of the operator and its
+/// inverse. `solve(b, x)` must set `x` to the solution of the system with right-hand side `b`, and
+/// `solve_adjoint` must do the same for the adjoint operator. The solves can be approximate (eg
+/// using an iterative solver), but the estimate will only be as accurate as the solves.
+pub fn estimate_condition_number<T: RlstScalar>(
+    size: usize,
+    apply: impl Fn(&[T], &mut [T]),
+    apply_adjoint: impl Fn(&[T], &mut [T]),
+    solve: impl Fn(&[T], &mut [T]),
+    solve_adjoint: impl Fn(&[T], &mut [T]),
+    max_iterations: usize,
+    tolerance: T::Real,
+) -> T::Real {
+    estimate_norm(
+        [size, size],
+        apply,
+        apply_adjoint,
+        max_iterations,
+        tolerance,
+    ) * estimate_norm(
+        [size, size],
+        solve,
+        solve_adjoint,
+        max_iterations,
+        tolerance,
+    )
+}
+
+/// Estimate the 2-norm of a dense matrix using power iteration
+pub fn estimate_dense_norm<T: RlstScalar>(
+    matrix: &DynamicArray<T, 2>,
+    max_iterations: usize,
+    tolerance: T::Real,
+) -> T::Real {
+    let shape = matrix.shape();
+    let data = matrix.data();
+    estimate_norm(
+        shape,
+        |x, y| {
+            for (i, yi) in y.iter_mut().enumerate() {
+                *yi = x
+                    .iter()
+                    .enumerate()
+                    .fold(T::zero(), |a, (j, xj)| a + data[j * shape[0] + i] * *xj);
+            }
+        },
+        |y, x| {
+            for (j, xj) in x.iter_mut().enumerate() {
+                *xj = data[j * shape[0]..(j + 1) * shape[0]]
+                    .iter()
+                    .zip(y)
+                    .fold(T::zero(), |a, (m, yi)| a + m.conj() * *yi);
+            }
+        },
+        max_iterations,
+        tolerance,
+    )
+}
+
+/// The energy norm of a vector with respect to a Hermitian positive definite operator
+///
+/// `apply(u, v)` must set `v` to the product of the operator and `u`: for example, the mass matrix
+/// of a function space can be used to compute the L2 norm of a function from its coefficients.
+pub fn energy_norm<T: RlstScalar>(apply: impl Fn(&[T], &mut [T]), u: &[T]) -> T::Real {
+    let mut v = vec![T::zero(); u.len()];
+    apply(u, &mut v);
+    u.iter()
+        .zip(&v)
+        .fold(T::zero(), |a, (ui, vi)| a + ui.conj() * *vi)
+        .abs()
+        .sqrt()
+}
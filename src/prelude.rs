@@ -0,0 +1,18 @@
+//! Commonly used types and functions
+//!
+//! This module re-exports the parts of the public API that are needed for most problems, together
+//! with the types from ndelement and ndgrid that are needed to create grids and function spaces.
+//! It can be glob imported:
+//!
+//! ```
+//! use bempp::prelude::*;
+//! ```
+pub use crate::boundary_assemblers::{BlockSystem, BoundaryAssembler, BoundaryAssemblerOptions};
+pub use crate::function::{FunctionSpace, FunctionSpaceTrait};
+pub use crate::solvers::{block_cg, block_pcg, cocg, SolverOptions, SolverResult};
+pub use crate::units::{Frequency, Length, LengthUnit, MeshUnits, Wavenumber};
+pub use crate::{helmholtz, laplace, shapes};
+
+pub use ndelement::ciarlet::LagrangeElementFamily;
+pub use ndelement::types::{Continuity, ReferenceCellType};
+pub use ndgrid::traits::{Entity, Grid, ParallelGrid, Topology};
@@ -0,0 +1,640 @@
+//! Iterative solvers
+//!
+//! The solvers in this module only need to be able to apply an operator to a block of vectors, so
+//! they can be used with dense matrices as well as with operators that are only available as a
+//! matrix-vector product. Blocks of vectors are stored column-major, with one column for each
+//! right-hand side: applying the operator to all the columns at once allows implementations of the
+//! operator to reuse work between right-hand sides.
+//!
+//! When a sequence of related systems is solved, information from earlier solves can be reused in
+//! two ways: the previous solution can be used as an initial guess (see
+//! [`SolverOptions::set_use_initial_guess`]), and a subspace that was slow to converge can be
+//! deflated (see [`deflated_block_cg`] and [`deflated_block_pcg`]). The deflation space must be
+//! supplied by the caller, for example the solutions of previous systems or approximate
+//! eigenvectors for the smallest eigenvalues. Extracting such approximate eigenvectors
+//! automatically from the Krylov spaces of earlier solves is not yet implemented.
+//!
+//! Only block CG (for Hermitian positive definite systems) and COCG (for complex symmetric
+//! systems) are available. Block GMRES for general non-symmetric systems is not yet implemented.
+use num::Zero;
+use rlst::RlstScalar;
+use std::cmp::Ordering;
+use std::time::Instant;
+
+/// Options for an iterative solver
+#[derive(Debug, Clone)]
+pub struct SolverOptions<R: RlstScalar<Real = R>> {
+    /// Relative residual at which a right-hand side is considered solved
+    tolerance: R,
+    /// Maximum number of iterations
+    max_iterations: usize,
+    /// Whether the initial contents of the solution are used as the initial guess
+    use_initial_guess: bool,
+}
+
+impl<R: RlstScalar<Real = R>> Default for SolverOptions<R> {
+    fn default() -> Self {
+        Self {
+            tolerance: num::cast::<f64, R>(1e-8).unwrap(),
+            max_iterations: 1000,
+            use_initial_guess: false,
+        }
+    }
+}
+
+impl<R: RlstScalar<Real = R>> SolverOptions<R> {
+    /// Set the relative residual at which a right-hand side is considered solved
+    pub fn set_tolerance(&mut self, tolerance: R) {
+        self.tolerance = tolerance;
+    }
+    /// Get the relative residual at which a right-hand side is considered solved
+    pub fn get_tolerance(&self) -> R {
+        self.tolerance
+    }
+    /// Set the maximum number of iterations
+    pub fn set_max_iterations(&mut self, max_iterations: usize) {
+        self.max_iterations = max_iterations;
+    }
+    /// Get the maximum number of iterations
+    pub fn get_max_iterations(&self) -> usize {
+        self.max_iterations
+    }
+    /// Set whether the initial contents of the solution are used as the initial guess
+    ///
+    /// If this is false (the default), the initial guess is zero. When solving a sequence of nearby
+    /// problems, such as a sweep over wavenumbers, using the solution of the previous problem as
+    /// the initial guess can reduce the number of iterations.
+    pub fn set_use_initial_guess(&mut self, use_initial_guess: bool) {
+        self.use_initial_guess = use_initial_guess;
+    }
+    /// Get whether the initial contents of the solution are used as the initial guess
+    pub fn get_use_initial_guess(&self) -> bool {
+        self.use_initial_guess
+    }
+}
+
+/// Information about the result of an iterative solve
+#[derive(Debug, Clone)]
+pub struct SolverResult<R: RlstScalar<Real = R>> {
+    /// Number of iterations performed
+    pub iterations: usize,
+    /// Whether every right-hand side was solved to the requested tolerance
+    pub converged: bool,
+    /// The final relative residual for each right-hand side
+    pub relative_residuals: Vec<R>,
+    /// The convergence history of the solve
+    pub history: ConvergenceHistory<R>,
+}
+
+/// The convergence history of an iterative solve
+///
+/// This records the residuals after each iteration and the time spent applying the operator and
+/// the preconditioner, so that solvers and preconditioners can be compared.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConvergenceHistory<R: RlstScalar<Real = R>> {
+    /// The relative residual of each right-hand side after each iteration
+    pub relative_residuals: Vec<Vec<R>>,
+    /// The time in seconds taken to apply the operator in each iteration
+    pub apply_times: Vec<f64>,
+    /// The time in seconds taken by each application of the preconditioner
+    ///
+    /// This is empty if no preconditioner was used.
+    pub preconditioner_times: Vec<f64>,
+}
+
+impl<R: RlstScalar<Real = R>> Default for ConvergenceHistory<R> {
+    fn default() -> Self {
+        Self {
+            relative_residuals: vec![],
+            apply_times: vec![],
+            preconditioner_times: vec![],
+        }
+    }
+}
+
+impl<R: RlstScalar<Real = R>> ConvergenceHistory<R> {
+    /// The total time in seconds spent applying the operator
+    pub fn total_apply_time(&self) -> f64 {
+        self.apply_times.iter().sum()
+    }
+    /// The total time in seconds spent applying the preconditioner
+    pub fn total_preconditioner_time(&self) -> f64 {
+        self.preconditioner_times.iter().sum()
+    }
+}
+
+/// Compute the matrix of inner products of the columns of two blocks
+///
+/// The result has a row for each column of `a` and a column for each column of `b`.
+fn inner_products<T: RlstScalar>(a: &[T], b: &[T], size: usize) -> Vec<T> {
+    b.chunks(size)
+        .flat_map(|bj| {
+            a.chunks(size).map(move |ai| {
+                ai.iter()
+                    .zip(bj)
+                    .fold(T::zero(), |s, (aik, bjk)| s + aik.conj() * *bjk)
+            })
+        })
+        .collect()
+}
+
+/// Solve a small dense system with multiple right-hand sides using Gaussian elimination
+///
+/// Returns `None` if the matrix is singular or contains NaN.
+fn dense_solve<T: RlstScalar>(mut matrix: Vec<T>, mut rhs: Vec<T>, n: usize) -> Option<Vec<T>> {
+    let ncols = rhs.len() / n;
+    for k in 0..n {
+        let mut pivot = k;
+        for i in k + 1..n {
+            if matrix[k * n + i]
+                .abs()
+                .partial_cmp(&matrix[k * n + pivot].abs())?
+                == Ordering::Greater
+            {
+                pivot = i;
+            }
+        }
+        if matrix[k * n + pivot].abs().partial_cmp(&T::Real::zero()) != Some(Ordering::Greater) {
+            return None;
+        }
+        if pivot != k {
+            for j in 0..n {
+                matrix.swap(j * n + k, j * n + pivot);
+            }
+            for j in 0..ncols {
+                rhs.swap(j * n + k, j * n + pivot);
+            }
+        }
+        for i in k + 1..n {
+            let factor = matrix[k * n + i] / matrix[k * n + k];
+            for j in k..n {
+                let value = matrix[j * n + k];
+                matrix[j * n + i] -= factor * value;
+            }
+            for j in 0..ncols {
+                let value = rhs[j * n + k];
+                rhs[j * n + i] -= factor * value;
+            }
+        }
+    }
+    for j in 0..ncols {
+        for k in (0..n).rev() {
+            let mut value = rhs[j * n + k];
+            for i in k + 1..n {
+                value -= matrix[i * n + k] * rhs[j * n + i];
+            }
+            rhs[j * n + k] = value / matrix[k * n + k];
+        }
+    }
+    Some(rhs)
+}
+
+/// Compute `a += b * c`, where `b` is a block and `c` is a small dense matrix
+///
+/// `c` has a row for each column of `b` and a column for each column of `a`.
+fn add_block_product<T: RlstScalar>(a: &mut [T], b: &[T], c: &[T], size: usize) {
+    let nb = b.len() / size;
+    for (aj, cj) in a.chunks_mut(size).zip(c.chunks(nb)) {
+        for (bi, coeff) in b.chunks(size).zip(cj) {
+            for (ajk, bik) in aj.iter_mut().zip(bi) {
+                *ajk += *bik * *coeff;
+            }
+        }
+    }
+}
+
+/// Compute `a[:, columns] += b * c`, where `b` is a block and `c` is a small dense matrix
+///
+/// `c` has a row for each column of `b` and a column for each entry of `columns`.
+fn add_block_product_to_columns<T: RlstScalar>(
+    a: &mut [T],
+    columns: &[usize],
+    b: &[T],
+    c: &[T],
+    size: usize,
+) {
+    let nb = b.len() / size;
+    for (j, cj) in columns.iter().zip(c.chunks(nb)) {
+        add_block_product(&mut a[j * size..(j + 1) * size], b, cj, size);
+    }
+}
+
+/// Copy some of the columns of a block into a new block
+fn select_columns<T: RlstScalar>(a: &[T], columns: &[usize], size: usize) -> Vec<T> {
+    columns
+        .iter()
+        .flat_map(|j| &a[j * size..(j + 1) * size])
+        .copied()
+        .collect()
+}
+
+/// Orthonormalise the columns of a block, dropping columns that are linearly dependent on the
+/// columns before them
+///
+/// A column is dropped if orthogonalising it against the previous columns reduces its norm by
+/// more than a factor of `drop_tolerance`.
+fn orthonormal_columns<T: RlstScalar>(a: &[T], size: usize, drop_tolerance: T::Real) -> Vec<T> {
+    let mut result: Vec<T> = vec![];
+    for col in a.chunks(size) {
+        let mut v = col.to_vec();
+        if !result.is_empty() {
+            // Orthogonalising twice keeps the columns orthogonal to working precision
+            for _ in 0..2 {
+                let coeffs = inner_products(&result, &v, size);
+                let minus_coeffs = coeffs.iter().map(|c| -*c).collect::<Vec<_>>();
+                add_block_product(&mut v, &result, &minus_coeffs, size);
+            }
+        }
+        let norm = column_norms(&v, size)[0];
+        if norm > drop_tolerance * column_norms(col, size)[0] {
+            result.extend(v.iter().map(|vi| vi.div_real(norm)));
+        }
+    }
+    result
+}
+
+/// The 2-norm of each column of a block
+fn column_norms<T: RlstScalar>(a: &[T], size: usize) -> Vec<T::Real> {
+    a.chunks(size)
+        .map(|col| {
+            col.iter()
+                .fold(T::Real::zero(), |s, v| s + v.abs() * v.abs())
+                .sqrt()
+        })
+        .collect()
+}
+
+/// Compute the residual `b - Ax` of the initial guess
+///
+/// If the options do not use the initial guess, `x` is set to zero first, so the residual is `b`
+/// and the operator is not applied.
+fn initial_residual<T: RlstScalar>(
+    apply: &dyn Fn(&[T], &mut [T]),
+    b: &[T],
+    x: &mut [T],
+    options: &SolverOptions<T::Real>,
+    history: &mut ConvergenceHistory<T::Real>,
+) -> Vec<T> {
+    if options.use_initial_guess {
+        let mut r = vec![T::zero(); b.len()];
+        let start = Instant::now();
+        apply(x, &mut r);
+        history.apply_times.push(start.elapsed().as_secs_f64());
+        for (ri, bi) in r.iter_mut().zip(b) {
+            *ri = *bi - *ri;
+        }
+        r
+    } else {
+        for i in x.iter_mut() {
+            *i = T::zero();
+        }
+        b.to_vec()
+    }
+}
+
+/// The norm of each column of a residual relative to the norm of the corresponding right-hand side
+fn relative_residual_norms<T: RlstScalar>(
+    r: &[T],
+    b_norms: &[T::Real],
+    size: usize,
+) -> Vec<T::Real> {
+    column_norms(r, size)
+        .iter()
+        .zip(b_norms)
+        .map(|(r, b)| *r / *b)
+        .collect()
+}
+
+/// Solve a Hermitian positive definite system with multiple right-hand sides using block CG
+///
+/// `apply(x, y)` must set the block `y` to the product of the operator and the block `x`. `b` is
+/// a block containing the right-hand sides, and the solutions are written into `x`. Each column
+/// of `b` must be non-zero. If [`SolverOptions::set_use_initial_guess`] has been used, the initial
+/// contents of `x` are used as the initial guess.
+///
+/// All right-hand sides share a single Krylov space, so this typically needs fewer iterations
+/// than solving for each right-hand side separately, and each iteration applies the operator to
+/// all the right-hand sides at once. Right-hand sides that have converged are dropped from the
+/// block, and search directions that are linearly dependent on the others are removed, so the
+/// iteration does not break down if some right-hand sides converge early or are linearly
+/// dependent. `apply` must therefore accept blocks with fewer columns than `b`.
+pub fn block_cg<T: RlstScalar>(
+    size: usize,
+    apply: impl Fn(&[T], &mut [T]),
+    b: &[T],
+    x: &mut [T],
+    options: &SolverOptions<T::Real>,
+) -> SolverResult<T::Real> {
+    preconditioned_block_cg(size, &apply, None, None, b, x, options)
+}
+
+/// Solve a Hermitian positive definite system with multiple right-hand sides using preconditioned block CG
+///
+/// This is the same as [`block_cg`], but with a Hermitian positive definite preconditioner:
+/// `precondition(r, z)` must set the block `z` to the product of the preconditioner and the block
+/// `r`.
+pub fn block_pcg<T: RlstScalar>(
+    size: usize,
+    apply: impl Fn(&[T], &mut [T]),
+    precondition: impl Fn(&[T], &mut [T]),
+    b: &[T],
+    x: &mut [T],
+    options: &SolverOptions<T::Real>,
+) -> SolverResult<T::Real> {
+    preconditioned_block_cg(size, &apply, Some(&precondition), None, b, x, options)
+}
+
+/// Solve a Hermitian positive definite system with multiple right-hand sides using deflated block CG
+///
+/// This is the same as [`block_cg`], but the block `deflation` (with `size` rows and one column
+/// for each vector, stored column-major) spans a subspace that is removed from the Krylov space.
+/// The error in this subspace is eliminated exactly at the start of the solve, so if it contains
+/// approximate eigenvectors for the smallest eigenvalues of the operator, the iteration converges
+/// as if those eigenvalues were not there. When solving a sequence of nearby problems, the
+/// deflation space can be recycled from one problem to the next.
+///
+/// The columns of `deflation` must be linearly independent, and `apply` must accept blocks with
+/// as many columns as `deflation` as well as blocks with up to as many columns as `b`. The operator is
+/// applied to the deflation space once at the start of the solve.
+pub fn deflated_block_cg<T: RlstScalar>(
+    size: usize,
+    apply: impl Fn(&[T], &mut [T]),
+    deflation: &[T],
+    b: &[T],
+    x: &mut [T],
+    options: &SolverOptions<T::Real>,
+) -> SolverResult<T::Real> {
+    preconditioned_block_cg(size, &apply, None, Some(deflation), b, x, options)
+}
+
+/// Solve a Hermitian positive definite system with multiple right-hand sides using deflated preconditioned block CG
+///
+/// This combines the preconditioner of [`block_pcg`] with the deflation space of
+/// [`deflated_block_cg`].
+pub fn deflated_block_pcg<T: RlstScalar>(
+    size: usize,
+    apply: impl Fn(&[T], &mut [T]),
+    precondition: impl Fn(&[T], &mut [T]),
+    deflation: &[T],
+    b: &[T],
+    x: &mut [T],
+    options: &SolverOptions<T::Real>,
+) -> SolverResult<T::Real> {
+    preconditioned_block_cg(
+        size,
+        &apply,
+        Some(&precondition),
+        Some(deflation),
+        b,
+        x,
+        options,
+    )
+}
+
+/// A deflation space `W`, together with `AW` and the matrix `E = W^H A W`
+struct Deflation<'a, T: RlstScalar> {
+    w: &'a [T],
+    aw: Vec<T>,
+    e: Vec<T>,
+    size: usize,
+}
+
+impl<'a, T: RlstScalar> Deflation<'a, T> {
+    fn new(
+        size: usize,
+        apply: &dyn Fn(&[T], &mut [T]),
+        w: &'a [T],
+        history: &mut ConvergenceHistory<T::Real>,
+    ) -> Self {
+        assert_eq!(w.len() % size, 0);
+        let mut aw = vec![T::zero(); w.len()];
+        let start = Instant::now();
+        apply(w, &mut aw);
+        history.apply_times.push(start.elapsed().as_secs_f64());
+        let e = inner_products(w, &aw, size);
+        Self { w, aw, e, size }
+    }
+
+    /// Solve `E c = rhs`
+    fn solve(&self, rhs: Vec<T>) -> Vec<T> {
+        dense_solve(self.e.clone(), rhs, self.w.len() / self.size)
+            .expect("The columns of the deflation space must be linearly independent")
+    }
+
+    /// Correct `x` so that the residual `r` is orthogonal to the deflation space
+    fn correct_solution(&self, x: &mut [T], r: &mut [T]) {
+        let c = self.solve(inner_products(self.w, r, self.size));
+        add_block_product(x, self.w, &c, self.size);
+        let minus_c = c.iter().map(|a| -*a).collect::<Vec<_>>();
+        add_block_product(r, &self.aw, &minus_c, self.size);
+    }
+
+    /// Project out the deflation space from a search direction: `z -= W E^-1 (AW)^H z`
+    fn project(&self, z: &mut [T]) {
+        let c = self.solve(inner_products(&self.aw, z, self.size));
+        let minus_c = c.iter().map(|a| -*a).collect::<Vec<_>>();
+        add_block_product(z, self.w, &minus_c, self.size);
+    }
+}
+
+fn preconditioned_block_cg<T: RlstScalar>(
+    size: usize,
+    apply: &dyn Fn(&[T], &mut [T]),
+    precondition: Option<&dyn Fn(&[T], &mut [T])>,
+    deflation: Option<&[T]>,
+    b: &[T],
+    x: &mut [T],
+    options: &SolverOptions<T::Real>,
+) -> SolverResult<T::Real> {
+    assert_eq!(b.len() % size, 0);
+    assert_eq!(x.len(), b.len());
+    let nrhs = b.len() / size;
+    let b_norms = column_norms(b, size);
+    if b_norms.iter().any(|n| *n == T::Real::zero()) {
+        panic!("Right-hand sides must be non-zero");
+    }
+    let mut history = ConvergenceHistory::default();
+
+    // Apply the preconditioner (if there is one) and record the time taken
+    let apply_preconditioner = |r: &[T], history: &mut ConvergenceHistory<T::Real>| {
+        if let Some(precondition) = precondition {
+            let mut z = vec![T::zero(); r.len()];
+            let start = Instant::now();
+            precondition(r, &mut z);
+            history
+                .preconditioner_times
+                .push(start.elapsed().as_secs_f64());
+            z
+        } else {
+            r.to_vec()
+        }
+    };
+
+    // Search directions whose norm is reduced by more than this factor when they are
+    // orthogonalised against the other directions are treated as linearly dependent
+    let epsilon: T::Real = num::Float::epsilon();
+    let drop_tolerance = epsilon.sqrt();
+
+    let mut r = initial_residual(apply, b, x, options, &mut history);
+    let deflation = deflation.map(|w| Deflation::new(size, apply, w, &mut history));
+    if let Some(deflation) = &deflation {
+        deflation.correct_solution(x, &mut r);
+    }
+    let mut relative_residuals = relative_residual_norms(&r, &b_norms, size);
+
+    // The right-hand sides that have not converged yet. Only these columns are updated, and the
+    // block of search directions only has directions for these columns.
+    let mut active = (0..nrhs)
+        .filter(|j| relative_residuals[*j] > options.tolerance)
+        .collect::<Vec<_>>();
+    let mut p = apply_preconditioner(&select_columns(&r, &active, size), &mut history);
+    if let Some(deflation) = &deflation {
+        deflation.project(&mut p);
+    }
+    p = orthonormal_columns(&p, size, drop_tolerance);
+
+    let mut iterations = 0;
+    while iterations < options.max_iterations && !active.is_empty() && !p.is_empty() {
+        iterations += 1;
+        let ndirections = p.len() / size;
+        let mut q = vec![T::zero(); p.len()];
+        let start = Instant::now();
+        apply(&p, &mut q);
+        history.apply_times.push(start.elapsed().as_secs_f64());
+
+        let pq = inner_products(&p, &q, size);
+        let pr = inner_products(&p, &select_columns(&r, &active, size), size);
+        let Some(alpha) = dense_solve(pq.clone(), pr, ndirections) else {
+            break;
+        };
+        add_block_product_to_columns(x, &active, &p, &alpha, size);
+        let minus_alpha = alpha.iter().map(|a| -*a).collect::<Vec<_>>();
+        add_block_product_to_columns(&mut r, &active, &q, &minus_alpha, size);
+
+        relative_residuals = relative_residual_norms(&r, &b_norms, size);
+        history.relative_residuals.push(relative_residuals.clone());
+        active.retain(|j| relative_residuals[*j] > options.tolerance);
+        if active.is_empty() {
+            break;
+        }
+
+        let mut z = apply_preconditioner(&select_columns(&r, &active, size), &mut history);
+        if let Some(deflation) = &deflation {
+            deflation.project(&mut z);
+        }
+        // Make the new directions A-orthogonal to the previous ones: z -= P (P^H A P)^-1 (AP)^H z
+        let Some(beta) = dense_solve(pq, inner_products(&q, &z, size), ndirections) else {
+            break;
+        };
+        let minus_beta = beta.iter().map(|b| -*b).collect::<Vec<_>>();
+        add_block_product(&mut z, &p, &minus_beta, size);
+        p = orthonormal_columns(&z, size, drop_tolerance);
+    }
+
+    SolverResult {
+        iterations,
+        converged: relative_residuals.iter().all(|r| *r <= options.tolerance),
+        relative_residuals,
+        history,
+    }
+}
+
+/// Solve a complex symmetric system with one or more right-hand sides using COCG
+///
+/// COCG (conjugate orthogonal conjugate gradient) is a variant of CG for matrices that are
+/// symmetric but not Hermitian, such as Galerkin discretisations of Helmholtz boundary operators.
+/// It uses short recurrences, so it needs much less memory and work per iteration than GMRES.
+///
+/// `apply(x, y)` must set the block `y` to the product of the operator and the block `x`. `b` is
+/// a block containing the right-hand sides, and the solutions are written into `x`. Each
+/// right-hand side has its own recurrence, but the operator is applied to all of them at once.
+/// Each column of `b` must be non-zero. If [`SolverOptions::set_use_initial_guess`] has been used,
+/// the initial contents of `x` are used as the initial guess.
+pub fn cocg<T: RlstScalar>(
+    size: usize,
+    apply: impl Fn(&[T], &mut [T]),
+    b: &[T],
+    x: &mut [T],
+    options: &SolverOptions<T::Real>,
+) -> SolverResult<T::Real> {
+    assert_eq!(b.len() % size, 0);
+    assert_eq!(x.len(), b.len());
+    let nrhs = b.len() / size;
+    let b_norms = column_norms(b, size);
+    if b_norms.iter().any(|n| *n == T::Real::zero()) {
+        panic!("Right-hand sides must be non-zero");
+    }
+    let mut history = ConvergenceHistory::default();
+
+    // The (unconjugated) bilinear form used by COCG
+    let bilinear = |a: &[T], b: &[T]| a.iter().zip(b).fold(T::zero(), |s, (i, j)| s + *i * *j);
+
+    let mut r = initial_residual(&apply, b, x, options, &mut history);
+    let mut relative_residuals = relative_residual_norms(&r, &b_norms, size);
+    let mut p = r.clone();
+    let mut q = vec![T::zero(); b.len()];
+    let mut rho = r.chunks(size).map(|c| bilinear(c, c)).collect::<Vec<_>>();
+    let mut active = relative_residuals
+        .iter()
+        .map(|r| *r > options.tolerance)
+        .collect::<Vec<_>>();
+    for (p, is_active) in p.chunks_mut(size).zip(&active) {
+        if !is_active {
+            for pi in p.iter_mut() {
+                *pi = T::zero();
+            }
+        }
+    }
+
+    let mut iterations = 0;
+    while iterations < options.max_iterations && active.iter().any(|a| *a) {
+        iterations += 1;
+        let start = Instant::now();
+        apply(&p, &mut q);
+        history.apply_times.push(start.elapsed().as_secs_f64());
+
+        for (j, is_active) in active.iter_mut().enumerate() {
+            if !*is_active {
+                continue;
+            }
+            let range = j * size..(j + 1) * size;
+            let pq = bilinear(&p[range.clone()], &q[range.clone()]);
+            if pq == T::zero() || rho[j] == T::zero() {
+                // Breakdown: this right-hand side cannot be improved any further
+                *is_active = false;
+                continue;
+            }
+            let alpha = rho[j] / pq;
+            for (xi, pi) in x[range.clone()].iter_mut().zip(&p[range.clone()]) {
+                *xi += alpha * *pi;
+            }
+            for (ri, qi) in r[range.clone()].iter_mut().zip(&q[range.clone()]) {
+                *ri -= alpha * *qi;
+            }
+            relative_residuals[j] = column_norms(&r[range.clone()], size)[0] / b_norms[j];
+            if relative_residuals[j] <= options.tolerance {
+                *is_active = false;
+                for pi in p[range].iter_mut() {
+                    *pi = T::zero();
+                }
+                continue;
+            }
+
+            let new_rho = bilinear(&r[range.clone()], &r[range.clone()]);
+            let beta = new_rho / rho[j];
+            rho[j] = new_rho;
+            for (pi, ri) in p[range.clone()].iter_mut().zip(&r[range]) {
+                *pi = *ri + beta * *pi;
+            }
+        }
+        history.relative_residuals.push(relative_residuals.clone());
+    }
+
+    SolverResult {
+        iterations,
+        converged: relative_residuals.iter().all(|r| *r <= options.tolerance),
+        relative_residuals,
+        history,
+    }
+}
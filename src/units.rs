@@ -0,0 +1,206 @@
+//! Physical units for lengths and wavenumbers
+//!
+//! Grids store coordinates as plain numbers and the Helmholtz assemblers take the wavenumber as a
+//! plain number, so the two are only consistent if they are given in the same length unit. The
+//! types in this module record the unit of each quantity, and [`MeshUnits`] converts them to the
+//! dimensionless values in the units of the grid's coordinates that should be passed to assembly.
+use crate::grid_tools::vertex_coordinates;
+use ndelement::types::ReferenceCellType;
+use ndgrid::traits::{Entity, Grid, Topology};
+use ndgrid::types::RealScalar;
+use num::Float;
+
+/// A unit of length
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LengthUnit {
+    /// Metres
+    Metre,
+    /// Centimetres
+    Centimetre,
+    /// Millimetres
+    Millimetre,
+    /// Micrometres
+    Micrometre,
+    /// Nanometres
+    Nanometre,
+    /// A custom unit, given as its length in metres
+    Custom(f64),
+}
+
+impl LengthUnit {
+    /// The length of this unit in metres
+    pub fn in_metres(&self) -> f64 {
+        match self {
+            LengthUnit::Metre => 1.0,
+            LengthUnit::Centimetre => 1e-2,
+            LengthUnit::Millimetre => 1e-3,
+            LengthUnit::Micrometre => 1e-6,
+            LengthUnit::Nanometre => 1e-9,
+            LengthUnit::Custom(m) => *m,
+        }
+    }
+}
+
+fn metres<T: RealScalar>(unit: LengthUnit) -> T {
+    num::cast::<f64, T>(unit.in_metres()).unwrap()
+}
+
+/// A length
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Length<T: RealScalar> {
+    metres: T,
+}
+
+impl<T: RealScalar> Length<T> {
+    /// Create a length from a value in the given unit
+    pub fn new(value: T, unit: LengthUnit) -> Self {
+        Self {
+            metres: value * metres(unit),
+        }
+    }
+    /// The value of this length in the given unit
+    pub fn value(&self, unit: LengthUnit) -> T {
+        self.metres / metres(unit)
+    }
+}
+
+/// A frequency
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frequency<T: RealScalar> {
+    hertz: T,
+}
+
+impl<T: RealScalar> Frequency<T> {
+    /// Create a frequency from a value in Hertz
+    pub fn from_hertz(hertz: T) -> Self {
+        Self { hertz }
+    }
+    /// The value of this frequency in Hertz
+    pub fn hertz(&self) -> T {
+        self.hertz
+    }
+}
+
+/// A wavenumber
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Wavenumber<T: RealScalar> {
+    per_metre: T,
+}
+
+impl<T: RealScalar> Wavenumber<T> {
+    /// Create a wavenumber from a value in radians per unit length
+    pub fn new(value: T, per: LengthUnit) -> Self {
+        Self {
+            per_metre: value / metres(per),
+        }
+    }
+    /// Create the wavenumber of a wave with the given wavelength
+    pub fn from_wavelength(wavelength: Length<T>) -> Self {
+        Self {
+            per_metre: two_pi::<T>() / wavelength.metres,
+        }
+    }
+    /// Create the wavenumber of a wave with the given frequency and wave speed (in metres per second)
+    pub fn from_frequency(frequency: Frequency<T>, wave_speed: T) -> Self {
+        Self {
+            per_metre: two_pi::<T>() * frequency.hertz / wave_speed,
+        }
+    }
+    /// The value of this wavenumber in radians per unit length
+    pub fn value(&self, per: LengthUnit) -> T {
+        self.per_metre * metres(per)
+    }
+    /// The wavelength
+    pub fn wavelength(&self) -> Length<T> {
+        Length {
+            metres: two_pi::<T>() / self.per_metre,
+        }
+    }
+}
+
+fn two_pi<T: RealScalar>() -> T {
+    num::cast::<f64, T>(2.0 * std::f64::consts::PI).unwrap()
+}
+
+/// The length unit used by the coordinates of a grid
+///
+/// This converts physical quantities to the dimensionless values that should be used when
+/// assembling operators on the grid, and converts dimensionless values back to physical quantities.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MeshUnits {
+    unit: LengthUnit,
+}
+
+impl MeshUnits {
+    /// Create new
+    pub fn new(unit: LengthUnit) -> Self {
+        Self { unit }
+    }
+    /// The length unit of the grid
+    pub fn unit(&self) -> LengthUnit {
+        self.unit
+    }
+    /// The value of a length in the units of the grid
+    pub fn length<T: RealScalar>(&self, length: Length<T>) -> T {
+        length.value(self.unit)
+    }
+    /// The length corresponding to a value in the units of the grid
+    pub fn dimensional_length<T: RealScalar>(&self, value: T) -> Length<T> {
+        Length::new(value, self.unit)
+    }
+    /// The value of a wavenumber in the units of the grid
+    ///
+    /// This is the value that should be passed to the Helmholtz assemblers.
+    pub fn wavenumber<T: RealScalar>(&self, wavenumber: Wavenumber<T>) -> T {
+        wavenumber.value(self.unit)
+    }
+    /// The wavenumber corresponding to a value in the units of the grid
+    pub fn dimensional_wavenumber<T: RealScalar>(&self, value: T) -> Wavenumber<T> {
+        Wavenumber::new(value, self.unit)
+    }
+}
+
+/// The length of the longest edge of a grid
+pub fn max_edge_length<T: RealScalar, G: Grid<T = T, EntityDescriptor = ReferenceCellType>>(
+    grid: &G,
+) -> T {
+    let coordinates = vertex_coordinates(grid);
+    grid.entity_iter(1)
+        .map(|edge| {
+            let v = edge.topology().sub_entity_iter(0).collect::<Vec<_>>();
+            let a = coordinates[v[0]];
+            let b = coordinates[v[1]];
+            Float::sqrt(
+                (a[0] - b[0]) * (a[0] - b[0])
+                    + (a[1] - b[1]) * (a[1] - b[1])
+                    + (a[2] - b[2]) * (a[2] - b[2]),
+            )
+        })
+        .fold(num::cast::<f64, T>(0.0).unwrap(), Float::max)
+}
+
+/// Check that a grid resolves a wave
+///
+/// This returns an error if the grid has fewer than `min_elements_per_wavelength` elements per
+/// wavelength of a wave with the given wavenumber. Checking the resolution of the grid before
+/// assembly catches wavenumbers and grids given in inconsistent units, which otherwise silently
+/// give wrong results.
+pub fn check_resolution<T: RealScalar, G: Grid<T = T, EntityDescriptor = ReferenceCellType>>(
+    grid: &G,
+    units: &MeshUnits,
+    wavenumber: Wavenumber<T>,
+    min_elements_per_wavelength: T,
+) -> Result<(), String> {
+    let h = max_edge_length(grid);
+    let wavelength = units.length(wavenumber.wavelength());
+    if wavelength < min_elements_per_wavelength * h {
+        Err(format!(
+            "Grid has {} elements per wavelength, but at least {} are required. Check that the \
+             wavenumber and the grid are given in consistent units.",
+            num::cast::<T, f64>(wavelength / h).unwrap(),
+            num::cast::<T, f64>(min_elements_per_wavelength).unwrap(),
+        ))
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,138 @@
+use approx::*;
+use bempp::analytic::{
+    charged_sphere_potential, laplace_exterior_harmonic, laplace_interior_harmonic,
+    legendre_polynomials, real_spherical_harmonic, sound_soft_sphere_scattering,
+    spherical_bessel_j, spherical_bessel_y,
+};
+use bempp::boundary_assemblers::BoundaryAssemblerOptions;
+use bempp::function::{FunctionSpace, FunctionSpaceTrait};
+use bempp::grid_tools::surface_area;
+use bempp::laplace;
+use cauchy::c64;
+use mpi::environment::Universe;
+use ndelement::ciarlet::LagrangeElementFamily;
+use ndelement::types::Continuity;
+use rlst::RandomAccessByRef;
+use std::f64::consts::PI;
+use std::sync::LazyLock;
+
+static MPI_UNIVERSE: LazyLock<Universe> = std::sync::LazyLock::new(|| {
+    mpi::initialize_with_threading(mpi::Threading::Multiple)
+        .unwrap()
+        .0
+});
+
+#[test]
+fn test_spherical_bessel() {
+    let x = 2.0;
+    let j = spherical_bessel_j(3, x);
+    let y = spherical_bessel_y(3, x);
+    assert_eq!(j.len(), 4);
+    assert_eq!(y.len(), 4);
+    assert_relative_eq!(j[0], x.sin() / x, epsilon = 1e-14);
+    assert_relative_eq!(j[1], x.sin() / x.powi(2) - x.cos() / x, epsilon = 1e-14);
+    assert_relative_eq!(y[0], -x.cos() / x, epsilon = 1e-14);
+    assert_relative_eq!(y[1], -x.cos() / x.powi(2) - x.sin() / x, epsilon = 1e-14);
+    // Wronskian: j_n y_{n-1} - j_{n-1} y_n = 1 / x^2
+    for (j, y) in j.windows(2).zip(y.windows(2)) {
+        assert_relative_eq!(j[1] * y[0] - j[0] * y[1], 1.0 / x.powi(2), epsilon = 1e-12);
+    }
+}
+
+#[test]
+fn test_spherical_harmonics() {
+    let p = legendre_polynomials(2, 0.3);
+    assert_relative_eq!(p[2], (3.0 * 0.09 - 1.0) / 2.0, epsilon = 1e-14);
+
+    let point = [0.3, -0.4, 1.2];
+    let r = 1.3;
+    assert_relative_eq!(
+        real_spherical_harmonic(0, 0, &point),
+        1.0 / (4.0 * PI).sqrt(),
+        epsilon = 1e-14
+    );
+    assert_relative_eq!(
+        real_spherical_harmonic(1, 0, &point),
+        (3.0 / (4.0 * PI)).sqrt() * point[2] / r,
+        epsilon = 1e-14
+    );
+    assert_relative_eq!(
+        real_spherical_harmonic(1, 1, &point).abs(),
+        (3.0 / (4.0 * PI)).sqrt() * point[0].abs() / r,
+        epsilon = 1e-14
+    );
+    assert_relative_eq!(
+        real_spherical_harmonic(1, -1, &point).abs(),
+        (3.0 / (4.0 * PI)).sqrt() * point[1].abs() / r,
+        epsilon = 1e-14
+    );
+}
+
+#[test]
+fn test_laplace_harmonics_are_harmonic() {
+    let h = 1e-3;
+    for (point, f) in [
+        (
+            [0.2, 0.3, -0.1],
+            laplace_interior_harmonic::<f64> as fn(usize, i32, f64, &[f64; 3]) -> f64,
+        ),
+        ([1.2, -0.8, 1.5], laplace_exterior_harmonic::<f64>),
+    ] {
+        let mut laplacian = -6.0 * f(2, 1, 1.0, &point);
+        for offset in [[h, 0.0, 0.0], [0.0, h, 0.0], [0.0, 0.0, h]] {
+            laplacian += f(2, 1, 1.0, &[0, 1, 2].map(|d| point[d] + offset[d]));
+            laplacian += f(2, 1, 1.0, &[0, 1, 2].map(|d| point[d] - offset[d]));
+        }
+        assert_abs_diff_eq!(laplacian / (h * h), 0.0, epsilon = 1e-4);
+    }
+
+    let boundary_point = [0.6, 0.0, 0.8];
+    assert_relative_eq!(
+        laplace_interior_harmonic(3, -2, 1.0, &boundary_point),
+        real_spherical_harmonic(3, -2, &boundary_point),
+        epsilon = 1e-14
+    );
+    assert_relative_eq!(
+        laplace_exterior_harmonic(3, -2, 1.0, &boundary_point),
+        real_spherical_harmonic(3, -2, &boundary_point),
+        epsilon = 1e-14
+    );
+}
+
+#[test]
+fn test_sound_soft_sphere_boundary_condition() {
+    let k = 3.0;
+    for point in [[1.0, 0.0, 0.0], [0.0, 0.0, -1.0], [0.0, 0.6, 0.8]] {
+        let scattered = sound_soft_sphere_scattering(k, 1.0, &point, 30);
+        let incident = c64::new(0.0, k * point[2]).exp();
+        assert_abs_diff_eq!((scattered + incident).norm(), 0.0, epsilon = 1e-10);
+    }
+}
+
+#[test]
+fn test_single_layer_of_uniform_density() {
+    let _ = *MPI_UNIVERSE;
+    let comm = mpi::topology::SimpleCommunicator::self_comm();
+    let grid = bempp::shapes::regular_sphere(3, 1, &comm);
+    let element = LagrangeElementFamily::<f64>::new(0, Continuity::Discontinuous);
+    let space = FunctionSpace::new(&grid, &element);
+    let options = BoundaryAssemblerOptions::default();
+    let matrix = laplace::assembler::single_layer(&options).assemble(&space, &space);
+
+    // A unit density on the unit sphere has total charge 4 pi
+    let potential = charged_sphere_potential(1.0, 4.0 * PI, &[1.0, 0.0, 0.0]);
+    assert_relative_eq!(potential, 1.0, epsilon = 1e-14);
+    assert_relative_eq!(
+        charged_sphere_potential(1.0, 4.0 * PI, &[0.1, 0.2, 0.0]),
+        potential,
+        epsilon = 1e-14
+    );
+
+    // The sum of all entries is the integral over the surface of the potential of a unit density
+    let n = space.global_size();
+    let total = (0..n)
+        .flat_map(|i| (0..n).map(move |j| (i, j)))
+        .map(|(i, j)| *matrix.get([i, j]).unwrap())
+        .sum::<f64>();
+    assert_relative_eq!(total / surface_area(&grid), potential, max_relative = 2e-2);
+}
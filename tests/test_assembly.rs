@@ -0,0 +1,101 @@
+use approx::*;
+use bempp::boundary_assemblers::BoundaryAssemblerOptions;
+use bempp::function::{FunctionSpace, FunctionSpaceTrait};
+use bempp::laplace;
+use bempp::shapes::screen_triangles;
+use mpi::environment::Universe;
+use ndelement::ciarlet::LagrangeElementFamily;
+use ndelement::types::{Continuity, ReferenceCellType};
+use ndgrid::traits::{Builder, ParallelBuilder};
+use ndgrid::SingleElementGridBuilder;
+use rlst::{RandomAccessByRef, RandomAccessMut, Shape};
+use std::sync::LazyLock;
+
+static MPI_UNIVERSE: LazyLock<Universe> = std::sync::LazyLock::new(|| {
+    mpi::initialize_with_threading(mpi::Threading::Multiple)
+        .unwrap()
+        .0
+});
+
+#[test]
+fn test_different_test_and_trial_rules() {
+    let _ = *MPI_UNIVERSE;
+    let comm = mpi::topology::SimpleCommunicator::self_comm();
+    let triangles = screen_triangles::<f64, _>(2, &comm);
+
+    // A screen of quadrilaterals parallel to the screen of triangles
+    let mut b = SingleElementGridBuilder::<f64>::new(3, (ReferenceCellType::Quadrilateral, 1));
+    for y in 0..3 {
+        for x in 0..3 {
+            b.add_point(3 * y + x, &[x as f64 / 2.0, y as f64 / 2.0, 1.0]);
+        }
+    }
+    for y in 0..2 {
+        for x in 0..2 {
+            let v = 3 * y + x;
+            b.add_cell(2 * y + x, &[v, v + 1, v + 3, v + 4]);
+        }
+    }
+    let quadrilaterals = b.create_parallel_grid_root(&comm);
+
+    let dp0 = LagrangeElementFamily::<f64>::new(0, Continuity::Discontinuous);
+    let p1 = LagrangeElementFamily::<f64>::new(1, Continuity::Standard);
+    let triangle_space = FunctionSpace::new(&triangles, &dp0);
+    let quadrilateral_space = FunctionSpace::new(&quadrilaterals, &p1);
+
+    let mut options = BoundaryAssemblerOptions::default();
+    options.set_regular_quadrature_degree(ReferenceCellType::Triangle, 12);
+    options.set_regular_quadrature_degree(ReferenceCellType::Quadrilateral, 16);
+    let assembler = laplace::assembler::single_layer(&options);
+
+    // The kernel is symmetric, so swapping the test and trial spaces transposes the matrix. Each
+    // element must be tabulated at the points of the rule for its own cell type for this to hold.
+    let matrix = assembler.assemble(&quadrilateral_space, &triangle_space);
+    let transpose = assembler.assemble(&triangle_space, &quadrilateral_space);
+    let ntriangle_dofs = triangle_space.global_size();
+    let nquadrilateral_dofs = quadrilateral_space.global_size();
+    assert_eq!(matrix.shape(), [ntriangle_dofs, nquadrilateral_dofs]);
+    assert_eq!(transpose.shape(), [nquadrilateral_dofs, ntriangle_dofs]);
+    for i in 0..ntriangle_dofs {
+        for j in 0..nquadrilateral_dofs {
+            assert_relative_eq!(
+                *matrix.get([i, j]).unwrap(),
+                *transpose.get([j, i]).unwrap(),
+                epsilon = 1e-14
+            );
+        }
+    }
+}
+
+#[test]
+fn test_singular_and_nonsingular_parts() {
+    let _ = *MPI_UNIVERSE;
+    let comm = mpi::topology::SimpleCommunicator::self_comm();
+    let grid = bempp::shapes::regular_sphere::<f64, _>(1, 1, &comm);
+    let element = LagrangeElementFamily::<f64>::new(1, Continuity::Standard);
+    let space = FunctionSpace::new(&grid, &element);
+    let options = BoundaryAssemblerOptions::default();
+    let assembler = laplace::assembler::single_layer(&options);
+
+    let matrix = assembler.assemble(&space, &space);
+    let mut sum = assembler.assemble_nonsingular(&space, &space);
+    let singular = assembler.assemble_singular(&space, &space);
+    for (i, row) in singular.indptr().windows(2).enumerate() {
+        for (j, value) in singular.indices()[row[0]..row[1]]
+            .iter()
+            .zip(&singular.data()[row[0]..row[1]])
+        {
+            *sum.get_mut([i, *j]).unwrap() += *value;
+        }
+    }
+    let n = space.global_size();
+    for i in 0..n {
+        for j in 0..n {
+            assert_relative_eq!(
+                *sum.get([i, j]).unwrap(),
+                *matrix.get([i, j]).unwrap(),
+                epsilon = 1e-14
+            );
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use approx::*;
+use bempp::boundary_assemblers::{BlockSystem, BoundaryAssemblerOptions};
+use bempp::function::{FunctionSpace, FunctionSpaceTrait};
+use bempp::laplace;
+use mpi::environment::Universe;
+use ndelement::ciarlet::LagrangeElementFamily;
+use ndelement::types::Continuity;
+use rlst::{CsrMatrix, RandomAccessByRef, RandomAccessMut, Shape};
+use std::sync::LazyLock;
+
+static MPI_UNIVERSE: LazyLock<Universe> = std::sync::LazyLock::new(|| {
+    mpi::initialize_with_threading(mpi::Threading::Multiple)
+        .unwrap()
+        .0
+});
+
+#[test]
+fn test_block_system_laplace() {
+    let _ = *MPI_UNIVERSE;
+    let comm = mpi::topology::SimpleCommunicator::self_comm();
+    let grid = bempp::shapes::regular_sphere(0, 1, &comm);
+    let dp0 = LagrangeElementFamily::<f64>::new(0, Continuity::Discontinuous);
+    let p1 = LagrangeElementFamily::<f64>::new(1, Continuity::Standard);
+    let dp0_space = FunctionSpace::new(&grid, &dp0);
+    let p1_space = FunctionSpace::new(&grid, &p1);
+    let options = BoundaryAssemblerOptions::default();
+
+    let single_layer = laplace::assembler::single_layer(&options).assemble(&dp0_space, &dp0_space);
+    let hypersingular = laplace::assembler::hypersingular(&options).assemble(&p1_space, &p1_space);
+
+    let mut system = BlockSystem::new();
+    let u0 = system.add_unknown(&dp0_space);
+    let u1 = system.add_unknown(&p1_space);
+    let e0 = system.add_equation(&dp0_space);
+    let e1 = system.add_equation(&p1_space);
+    system.add_operator(
+        e0,
+        u0,
+        2.0,
+        &laplace::assembler::single_layer(&options),
+        &dp0_space,
+        &dp0_space,
+    );
+    system.add_operator(
+        e1,
+        u1,
+        -1.0,
+        &laplace::assembler::hypersingular(&options),
+        &p1_space,
+        &p1_space,
+    );
+    system.add_rhs(e1, &vec![1.0; p1_space.global_size()]);
+
+    let n0 = dp0_space.global_size();
+    let n1 = p1_space.global_size();
+    let matrix = system.matrix();
+    assert_eq!(matrix.shape(), [n0 + n1, n0 + n1]);
+    for i in 0..n0 {
+        for j in 0..n0 {
+            assert_relative_eq!(
+                *matrix.get([i, j]).unwrap(),
+                2.0 * *single_layer.get([i, j]).unwrap(),
+                epsilon = 1e-12
+            );
+        }
+        for j in 0..n1 {
+            assert_eq!(*matrix.get([i, n0 + j]).unwrap(), 0.0);
+        }
+    }
+    for i in 0..n1 {
+        for j in 0..n1 {
+            assert_relative_eq!(
+                *matrix.get([n0 + i, n0 + j]).unwrap(),
+                -*hypersingular.get([i, j]).unwrap(),
+                epsilon = 1e-12
+            );
+        }
+    }
+
+    let rhs = system.rhs();
+    assert_eq!(rhs.len(), n0 + n1);
+    assert_eq!(rhs[0], 0.0);
+    assert_eq!(rhs[n0], 1.0);
+
+    let parts = system.split_solution(&rhs);
+    assert_eq!(parts[u0].len(), n0);
+    assert_eq!(parts[u1].len(), n1);
+}
+
+#[test]
+fn test_block_system_with_sized_unknowns() {
+    let _ = *MPI_UNIVERSE;
+    let comm = mpi::topology::SimpleCommunicator::self_comm();
+    let grid = bempp::shapes::regular_sphere(0, 1, &comm);
+    let p1 = LagrangeElementFamily::<f64>::new(1, Continuity::Standard);
+    let space = FunctionSpace::new(&grid, &p1);
+    let n = space.global_size();
+
+    let mut system = BlockSystem::new();
+    let volume = system.add_unknown_with_size(3);
+    let surface = system.add_unknown(&space);
+    let e0 = system.add_equation_with_size(3);
+    let e1 = system.add_equation(&space);
+    system.add_term(e0, volume, 1.0, rlst::rlst_dynamic_array2!(f64, [3, 3]));
+    system.add_rhs(e0, &[1.0, 2.0, 3.0]);
+    system.add_rhs(e1, &vec![4.0; n]);
+
+    assert_eq!(system.shape(), [3 + n, 3 + n]);
+    assert_eq!(system.rhs()[..4], [1.0, 2.0, 3.0, 4.0]);
+    let parts = system.split_solution(&vec![0.0; 3 + n]);
+    assert_eq!(parts[volume].len(), 3);
+    assert_eq!(parts[surface].len(), n);
+}
+
+#[test]
+fn test_block_system_sparse_term() {
+    let mut system = BlockSystem::<f64>::new();
+    let u = system.add_unknown_with_size(3);
+    let e = system.add_equation_with_size(2);
+    let sparse = CsrMatrix::from_aij([2, 3], &[0, 1, 1], &[2, 0, 1], &[1.0, 2.0, 3.0]).unwrap();
+    system.add_sparse_term(e, u, 2.0, sparse);
+    let mut dense = rlst::rlst_dynamic_array2!(f64, [2, 3]);
+    *dense.get_mut([1, 1]).unwrap() = 1.0;
+    system.add_term(e, u, 1.0, dense);
+
+    let matrix = system.matrix();
+    let expected = [[0.0, 0.0, 2.0], [4.0, 7.0, 0.0]];
+    for (i, row) in expected.iter().enumerate() {
+        for (j, value) in row.iter().enumerate() {
+            assert_eq!(*matrix.get([i, j]).unwrap(), *value);
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use approx::*;
+use bempp::boundary_assemblers::BoundaryAssemblerOptions;
+use bempp::function::{FunctionSpace, FunctionSpaceTrait};
+use bempp::laplace;
+use mpi::environment::Universe;
+use ndelement::ciarlet::LagrangeElementFamily;
+use ndelement::types::Continuity;
+use rlst::RandomAccessByRef;
+use std::sync::LazyLock;
+
+static MPI_UNIVERSE: LazyLock<Universe> = std::sync::LazyLock::new(|| {
+    mpi::initialize_with_threading(mpi::Threading::Multiple)
+        .unwrap()
+        .0
+});
+
+#[test]
+fn test_constant_coefficients() {
+    let _ = *MPI_UNIVERSE;
+    let comm = mpi::topology::SimpleCommunicator::self_comm();
+    let grid = bempp::shapes::regular_sphere(0, 1, &comm);
+    let element = LagrangeElementFamily::<f64>::new(0, Continuity::Discontinuous);
+    let space = FunctionSpace::new(&grid, &element);
+    let options = BoundaryAssemblerOptions::default();
+
+    let matrix = laplace::assembler::single_layer(&options).assemble(&space, &space);
+    let scaled = laplace::assembler::single_layer(&options)
+        .with_coefficients(|_| 2.0, |_| 3.0)
+        .assemble(&space, &space);
+
+    for i in 0..space.global_size() {
+        for j in 0..space.global_size() {
+            assert_relative_eq!(
+                *scaled.get([i, j]).unwrap(),
+                6.0 * *matrix.get([i, j]).unwrap(),
+                epsilon = 1e-12
+            );
+        }
+    }
+}
+
+#[test]
+fn test_varying_coefficients_bounds() {
+    let _ = *MPI_UNIVERSE;
+    let comm = mpi::topology::SimpleCommunicator::self_comm();
+    let grid = bempp::shapes::regular_sphere(1, 1, &comm);
+    let element = LagrangeElementFamily::<f64>::new(0, Continuity::Discontinuous);
+    let space = FunctionSpace::new(&grid, &element);
+    let options = BoundaryAssemblerOptions::default();
+
+    let matrix = laplace::assembler::single_layer(&options).assemble(&space, &space);
+    // The coefficient is between 1 and 2 on the unit sphere, and the single layer kernel is
+    // positive, so each entry is between 1 and 2 times the unscaled entry
+    let scaled = laplace::assembler::single_layer(&options)
+        .with_coefficients(|x: &[f64; 3]| 1.0 + x[2] * x[2], |_| 1.0)
+        .assemble(&space, &space);
+
+    for i in 0..space.global_size() {
+        for j in 0..space.global_size() {
+            let a = *matrix.get([i, j]).unwrap();
+            let b = *scaled.get([i, j]).unwrap();
+            assert!(b >= a * (1.0 - 1e-12));
+            assert!(b <= 2.0 * a * (1.0 + 1e-12));
+        }
+    }
+}
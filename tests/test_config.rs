@@ -0,0 +1,185 @@
+use approx::*;
+use bempp::boundary_assemblers::BoundaryAssemblerOptions;
+use bempp::config::{KernelConfig, RunConfig};
+use bempp::function::{FunctionSpace, FunctionSpaceTrait};
+use bempp::shapes::regular_sphere;
+use bempp::{helmholtz, laplace};
+use cauchy::c64;
+use mpi::environment::Universe;
+use ndelement::ciarlet::LagrangeElementFamily;
+use ndelement::types::{Continuity, ReferenceCellType};
+use rlst::RandomAccessByRef;
+use std::sync::LazyLock;
+
+static MPI_UNIVERSE: LazyLock<Universe> = std::sync::LazyLock::new(|| {
+    mpi::initialize_with_threading(mpi::Threading::Multiple)
+        .unwrap()
+        .0
+});
+
+#[test]
+fn test_default_config() {
+    let config = RunConfig::default();
+    let options = config.assembler_options();
+    let default_options = BoundaryAssemblerOptions::default();
+    assert_eq!(
+        options.quadrature_degrees,
+        default_options.quadrature_degrees
+    );
+    assert_eq!(
+        options.singular_quadrature_degrees,
+        default_options.singular_quadrature_degrees
+    );
+    assert_eq!(options.batch_size, default_options.batch_size);
+
+    let solver_options = config.solver_options::<f64>();
+    assert_eq!(solver_options.get_tolerance(), 1e-8);
+    assert_eq!(solver_options.get_max_iterations(), 1000);
+}
+
+#[test]
+fn test_config_options() {
+    let config = RunConfig {
+        kernel: KernelConfig::Helmholtz { wavenumber: 2.5 },
+        triangle_quadrature_degree: 12,
+        quadrilateral_quadrature_degree: 16,
+        triangle_triangle_singular_quadrature_degree: 6,
+        triangle_quadrilateral_singular_quadrature_degree: 7,
+        quadrilateral_triangle_singular_quadrature_degree: 8,
+        quadrilateral_quadrilateral_singular_quadrature_degree: 9,
+        batch_size: 32,
+        solver_tolerance: 1e-5,
+        solver_max_iterations: 50,
+    };
+    let options = config.assembler_options();
+    assert_eq!(
+        options.get_regular_quadrature_degree(ReferenceCellType::Triangle),
+        Some(12)
+    );
+    assert_eq!(
+        options.get_regular_quadrature_degree(ReferenceCellType::Quadrilateral),
+        Some(16)
+    );
+    for (cell_types, degree) in [
+        (
+            (ReferenceCellType::Triangle, ReferenceCellType::Triangle),
+            6,
+        ),
+        (
+            (
+                ReferenceCellType::Triangle,
+                ReferenceCellType::Quadrilateral,
+            ),
+            7,
+        ),
+        (
+            (
+                ReferenceCellType::Quadrilateral,
+                ReferenceCellType::Triangle,
+            ),
+            8,
+        ),
+        (
+            (
+                ReferenceCellType::Quadrilateral,
+                ReferenceCellType::Quadrilateral,
+            ),
+            9,
+        ),
+    ] {
+        assert_eq!(
+            options.get_singular_quadrature_degree(cell_types),
+            Some(degree)
+        );
+    }
+    assert_eq!(options.get_batch_size(), 32);
+
+    let solver_options = config.solver_options::<f32>();
+    assert_eq!(solver_options.get_tolerance(), 1e-5);
+    assert_eq!(solver_options.get_max_iterations(), 50);
+}
+
+#[test]
+fn test_config_laplace_assembler() {
+    let _ = *MPI_UNIVERSE;
+    let comm = mpi::topology::SimpleCommunicator::self_comm();
+    let grid = regular_sphere::<f64, _>(0, 1, &comm);
+    let element = LagrangeElementFamily::<f64>::new(0, Continuity::Discontinuous);
+    let space = FunctionSpace::new(&grid, &element);
+
+    let config = RunConfig::default();
+    assert_eq!(config.kernel, KernelConfig::Laplace);
+    let options = config.assembler_options();
+    let matrix = laplace::assembler::single_layer(&options).assemble(&space, &space);
+    let config_matrix = config
+        .laplace_single_layer::<f64>(&options)
+        .assemble(&space, &space);
+
+    let n = space.global_size();
+    for i in 0..n {
+        for j in 0..n {
+            assert_relative_eq!(
+                *config_matrix.get([i, j]).unwrap(),
+                *matrix.get([i, j]).unwrap(),
+                epsilon = 1e-14
+            );
+        }
+    }
+}
+
+#[test]
+fn test_config_helmholtz_assembler() {
+    let _ = *MPI_UNIVERSE;
+    let comm = mpi::topology::SimpleCommunicator::self_comm();
+    let grid = regular_sphere::<f64, _>(0, 1, &comm);
+    let element = LagrangeElementFamily::<c64>::new(0, Continuity::Discontinuous);
+    let space = FunctionSpace::new(&grid, &element);
+
+    let config = RunConfig {
+        kernel: KernelConfig::Helmholtz { wavenumber: 2.5 },
+        ..Default::default()
+    };
+    let options = config.assembler_options();
+    let matrix = helmholtz::assembler::single_layer(2.5, &options).assemble(&space, &space);
+    let config_matrix = config
+        .helmholtz_single_layer::<c64>(&options)
+        .assemble(&space, &space);
+
+    let n = space.global_size();
+    for i in 0..n {
+        for j in 0..n {
+            let value = *config_matrix.get([i, j]).unwrap();
+            let expected = *matrix.get([i, j]).unwrap();
+            assert_relative_eq!(value.re, expected.re, epsilon = 1e-14);
+            assert_relative_eq!(value.im, expected.im, epsilon = 1e-14);
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "Helmholtz assembler requested for a run with the Laplace kernel")]
+fn test_config_helmholtz_assembler_with_laplace_kernel() {
+    let config = RunConfig::default();
+    let options = config.assembler_options();
+    let _ = config.helmholtz_single_layer::<c64>(&options);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_config_serde_round_trip() {
+    let config = RunConfig {
+        kernel: KernelConfig::Helmholtz { wavenumber: 2.5 },
+        triangle_quadrature_degree: 12,
+        batch_size: 32,
+        solver_tolerance: 1e-5,
+        ..Default::default()
+    };
+    let json = serde_json::to_string(&config).unwrap();
+    let round_trip: RunConfig = serde_json::from_str(&json).unwrap();
+    assert_eq!(round_trip, config);
+
+    let config = RunConfig::default();
+    let json = serde_json::to_string(&config).unwrap();
+    let round_trip: RunConfig = serde_json::from_str(&json).unwrap();
+    assert_eq!(round_trip, config);
+}
@@ -0,0 +1,86 @@
+use approx::*;
+use bempp::boundary_assemblers::BoundaryAssemblerOptions;
+use bempp::function::{FunctionSpace, FunctionSpaceTrait};
+use bempp::laplace;
+use mpi::environment::Universe;
+use ndelement::ciarlet::LagrangeElementFamily;
+use ndelement::types::{Continuity, ReferenceCellType};
+use ndgrid::traits::{Builder, ParallelBuilder};
+use ndgrid::SingleElementGridBuilder;
+use rlst::RandomAccessByRef;
+use std::sync::LazyLock;
+
+static MPI_UNIVERSE: LazyLock<Universe> = std::sync::LazyLock::new(|| {
+    mpi::initialize_with_threading(mpi::Threading::Multiple)
+        .unwrap()
+        .0
+});
+
+#[test]
+fn test_far_field_dp0() {
+    let _ = *MPI_UNIVERSE;
+    let comm = mpi::topology::SimpleCommunicator::self_comm();
+    let grid = bempp::shapes::regular_sphere(1, 1, &comm);
+    let element = LagrangeElementFamily::<f64>::new(0, Continuity::Discontinuous);
+    let space = FunctionSpace::new(&grid, &element);
+    let options = BoundaryAssemblerOptions::default();
+    let assembler = laplace::assembler::single_layer(&options);
+
+    let matrix = assembler.assemble(&space, &space);
+    let (far_field, near_pairs) = assembler.assemble_far_field(&space, &space, 0.5);
+
+    let ncells = space.global_size();
+    assert!(near_pairs.len() > ncells);
+    assert!(near_pairs.len() < ncells * ncells);
+
+    for test_cell in 0..ncells {
+        let i = space.cell_dofs(test_cell).unwrap()[0];
+        for trial_cell in 0..ncells {
+            let j = space.cell_dofs(trial_cell).unwrap()[0];
+            if near_pairs.contains(&[test_cell, trial_cell]) {
+                assert_eq!(*far_field.get([i, j]).unwrap(), 0.0);
+            } else {
+                assert_relative_eq!(
+                    *far_field.get([i, j]).unwrap(),
+                    *matrix.get([i, j]).unwrap(),
+                    epsilon = 1e-14
+                );
+            }
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "flat cells")]
+fn test_far_field_curved_cell() {
+    let _ = *MPI_UNIVERSE;
+    let comm = mpi::topology::SimpleCommunicator::self_comm();
+    // A degree 2 triangle with one edge bent out of the plane
+    let mut b = SingleElementGridBuilder::<f64>::new(3, (ReferenceCellType::Triangle, 2));
+    b.add_point(0, &[0.0, 0.0, 0.0]);
+    b.add_point(1, &[1.0, 0.0, 0.0]);
+    b.add_point(2, &[0.0, 1.0, 0.0]);
+    b.add_point(3, &[0.5, 0.5, 0.3]);
+    b.add_point(4, &[0.0, 0.5, 0.0]);
+    b.add_point(5, &[0.5, 0.0, 0.0]);
+    b.add_cell(0, &[0, 1, 2, 3, 4, 5]);
+    let grid = b.create_parallel_grid_root(&comm);
+    let element = LagrangeElementFamily::<f64>::new(0, Continuity::Discontinuous);
+    let space = FunctionSpace::new(&grid, &element);
+    let options = BoundaryAssemblerOptions::default();
+    let assembler = laplace::assembler::single_layer(&options);
+    let _ = assembler.assemble_far_field(&space, &space, 0.5);
+}
+
+#[test]
+#[should_panic(expected = "separation must be non-negative")]
+fn test_far_field_negative_separation() {
+    let _ = *MPI_UNIVERSE;
+    let comm = mpi::topology::SimpleCommunicator::self_comm();
+    let grid = bempp::shapes::regular_sphere(0, 1, &comm);
+    let element = LagrangeElementFamily::<f64>::new(0, Continuity::Discontinuous);
+    let space = FunctionSpace::new(&grid, &element);
+    let options = BoundaryAssemblerOptions::default();
+    let assembler = laplace::assembler::single_layer(&options);
+    let _ = assembler.assemble_far_field(&space, &space, -0.1);
+}
@@ -0,0 +1,92 @@
+use bempp::boundary_assemblers::BoundaryAssemblerOptions;
+use bempp::fingerprint::OperatorFingerprint;
+use bempp::function::FunctionSpace;
+use bempp::laplace;
+use mpi::environment::Universe;
+use ndelement::ciarlet::LagrangeElementFamily;
+use ndelement::types::Continuity;
+use std::sync::LazyLock;
+
+static MPI_UNIVERSE: LazyLock<Universe> = std::sync::LazyLock::new(|| {
+    mpi::initialize_with_threading(mpi::Threading::Multiple)
+        .unwrap()
+        .0
+});
+
+#[test]
+fn test_assembly_independent_of_thread_count() {
+    let _ = *MPI_UNIVERSE;
+    let comm = mpi::topology::SimpleCommunicator::self_comm();
+    let grid = bempp::shapes::regular_sphere(2, 1, &comm);
+    let element = LagrangeElementFamily::<f64>::new(1, Continuity::Standard);
+    let space = FunctionSpace::new(&grid, &element);
+    let mut options = BoundaryAssemblerOptions::default();
+    options.set_batch_size(16);
+    let assembler = laplace::assembler::double_layer(&options);
+
+    let fingerprints = [1, 4]
+        .iter()
+        .map(|n| {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(*n)
+                .build()
+                .unwrap();
+            let matrix = pool.install(|| assembler.assemble(&space, &space));
+            OperatorFingerprint::new(&matrix, 20, 1e-12)
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(fingerprints[0], fingerprints[1]);
+}
+
+#[test]
+fn test_fingerprint_round_trip() {
+    let _ = *MPI_UNIVERSE;
+    let comm = mpi::topology::SimpleCommunicator::self_comm();
+    let grid = bempp::shapes::regular_sphere(0, 1, &comm);
+    let element = LagrangeElementFamily::<f64>::new(0, Continuity::Discontinuous);
+    let space = FunctionSpace::new(&grid, &element);
+    let options = BoundaryAssemblerOptions::default();
+
+    let matrix = laplace::assembler::single_layer(&options).assemble(&space, &space);
+    let fingerprint = OperatorFingerprint::new(&matrix, 10, 1e-10);
+    assert_eq!(fingerprint.samples.len(), 10);
+
+    let stored = fingerprint.to_string();
+    let loaded = stored.parse::<OperatorFingerprint>().unwrap();
+    assert_eq!(loaded.shape, [8, 8]);
+    assert_eq!(loaded.hash, fingerprint.hash);
+    assert!(loaded.matches(&fingerprint));
+    assert!(fingerprint.matches(&loaded));
+}
+
+#[test]
+fn test_fingerprint_mismatch() {
+    let _ = *MPI_UNIVERSE;
+    let comm = mpi::topology::SimpleCommunicator::self_comm();
+    let grid = bempp::shapes::regular_sphere(0, 1, &comm);
+    let element = LagrangeElementFamily::<f64>::new(0, Continuity::Discontinuous);
+    let space = FunctionSpace::new(&grid, &element);
+    let options = BoundaryAssemblerOptions::default();
+
+    let matrix = laplace::assembler::single_layer(&options).assemble(&space, &space);
+    let fingerprint = OperatorFingerprint::new(&matrix, 10, 1e-10);
+
+    // A sampled entry that changed by more than the tolerance relative to its own magnitude
+    let mut changed_sample = fingerprint.clone();
+    changed_sample.samples[0].1[0] *= 1.0 + 1e-8;
+    assert!(!fingerprint.matches(&changed_sample));
+    assert!(!changed_sample.matches(&fingerprint));
+
+    // A different hash
+    let mut changed_hash = fingerprint.clone();
+    changed_hash.hash ^= 1;
+    assert!(!fingerprint.matches(&changed_hash));
+    assert!(!changed_hash.matches(&fingerprint));
+
+    // A different tolerance
+    let mut changed_tolerance = fingerprint.clone();
+    changed_tolerance.tolerance = 1e-2;
+    assert!(!fingerprint.matches(&changed_tolerance));
+    assert!(!changed_tolerance.matches(&fingerprint));
+}
@@ -0,0 +1,85 @@
+use approx::*;
+use bempp::boundary_assemblers::BoundaryAssemblerOptions;
+use bempp::function::{
+    centre_of_charge, first_moments, integral, mean_value, multipole_moments, normal_dipole_moment,
+    patch_integral, FunctionSpace, FunctionSpaceTrait,
+};
+use bempp::grid_tools::{cell_areas, surface_area, vertex_coordinates};
+use mpi::environment::Universe;
+use ndelement::ciarlet::LagrangeElementFamily;
+use ndelement::types::{Continuity, ReferenceCellType};
+use std::sync::LazyLock;
+
+static MPI_UNIVERSE: LazyLock<Universe> = std::sync::LazyLock::new(|| {
+    mpi::initialize_with_threading(mpi::Threading::Multiple)
+        .unwrap()
+        .0
+});
+
+#[test]
+fn test_functionals_constant() {
+    let _ = *MPI_UNIVERSE;
+    let comm = mpi::topology::SimpleCommunicator::self_comm();
+    let grid = bempp::shapes::regular_sphere::<f64, _>(2, 1, &comm);
+    let element = LagrangeElementFamily::<f64>::new(1, Continuity::Standard);
+    let space = FunctionSpace::new(&grid, &element);
+    let ones = vec![1.0; space.local_size()];
+    let options = BoundaryAssemblerOptions::default();
+
+    assert_relative_eq!(
+        integral(&space, &ones, &options),
+        surface_area(&grid),
+        epsilon = 1e-10
+    );
+    assert_relative_eq!(mean_value(&space, &ones, &options), 1.0, epsilon = 1e-10);
+
+    let areas = cell_areas(&grid);
+    assert_relative_eq!(
+        patch_integral(&space, &ones, &[0, 3], &options),
+        areas[0] + areas[3],
+        epsilon = 1e-10
+    );
+
+    // The grid is symmetric about the origin, so the first moments of a constant vanish
+    for m in first_moments(&space, &ones, &options) {
+        assert_abs_diff_eq!(m, 0.0, epsilon = 1e-10);
+    }
+    for c in centre_of_charge(&space, &ones, &options) {
+        assert_abs_diff_eq!(c, 0.0, epsilon = 1e-10);
+    }
+    for d in normal_dipole_moment(&space, &ones, &options) {
+        assert_abs_diff_eq!(d, 0.0, epsilon = 1e-10);
+    }
+
+    let moments = multipole_moments(&space, &ones, [0.0, 0.0, 1.0], &options);
+    assert_relative_eq!(moments.monopole, surface_area(&grid), epsilon = 1e-10);
+    assert_relative_eq!(moments.dipole[2], -surface_area(&grid), epsilon = 1e-10);
+}
+
+#[test]
+fn test_functionals_quadrature_degree() {
+    let _ = *MPI_UNIVERSE;
+    let comm = mpi::topology::SimpleCommunicator::self_comm();
+    let grid = bempp::shapes::regular_sphere::<f64, _>(1, 1, &comm);
+    let element = LagrangeElementFamily::<f64>::new(1, Continuity::Standard);
+    let space = FunctionSpace::new(&grid, &element);
+    let coordinates = vertex_coordinates(&grid);
+    let mut x = vec![0.0; space.local_size()];
+    for (v, c) in coordinates.iter().enumerate() {
+        x[space.get_local_dof_numbers(0, v)[0]] = c[0];
+    }
+
+    let options = BoundaryAssemblerOptions::default();
+    let mut one_point = BoundaryAssemblerOptions::default();
+    one_point.set_regular_quadrature_degree(ReferenceCellType::Triangle, 1);
+
+    // A one point rule is exact for piecewise linear functions, but not for their moments
+    assert_relative_eq!(
+        integral(&space, &x, &one_point),
+        integral(&space, &x, &options),
+        epsilon = 1e-10
+    );
+    let moment = first_moments(&space, &x, &options)[0];
+    let one_point_moment = first_moments(&space, &x, &one_point)[0];
+    assert!((moment - one_point_moment).abs() > 1e-3);
+}
@@ -0,0 +1,307 @@
+use approx::*;
+use bempp::function::{gather_to_root, trace_matrix, FunctionSpace, FunctionSpaceTrait};
+use bempp::grid_tools::{
+    boundary_edges, boundary_loops, cell_areas, cell_curvatures, edge_chains, gather_grid_to_root,
+    heal_mesh, surface_area, vertex_curvatures, volume_boundary, HealingReport,
+};
+use bempp::shapes::{regular_sphere, screen_quadrilaterals, screen_triangles};
+use mpi::environment::Universe;
+use ndelement::ciarlet::LagrangeElementFamily;
+use ndelement::types::{Continuity, ReferenceCellType};
+use ndgrid::traits::Grid;
+use rlst::Shape;
+use std::sync::LazyLock;
+
+static MPI_UNIVERSE: LazyLock<Universe> = std::sync::LazyLock::new(|| {
+    mpi::initialize_with_threading(mpi::Threading::Multiple)
+        .unwrap()
+        .0
+});
+
+#[test]
+fn test_boundary_closed_surface() {
+    let _ = *MPI_UNIVERSE;
+    let comm = mpi::topology::SimpleCommunicator::self_comm();
+    let grid = regular_sphere::<f64, _>(2, 1, &comm);
+
+    assert!(boundary_edges(&grid).is_empty());
+    assert!(boundary_loops(&grid).is_empty());
+}
+
+#[test]
+fn test_boundary_screen_triangles() {
+    let _ = *MPI_UNIVERSE;
+    let comm = mpi::topology::SimpleCommunicator::self_comm();
+    let grid = screen_triangles::<f64, _>(4, &comm);
+
+    assert_eq!(boundary_edges(&grid).len(), 16);
+    let loops = boundary_loops(&grid);
+    assert_eq!(loops.len(), 1);
+    assert_eq!(loops[0].len(), 16);
+}
+
+#[test]
+fn test_boundary_screen_quadrilaterals() {
+    let _ = *MPI_UNIVERSE;
+    let comm = mpi::topology::SimpleCommunicator::self_comm();
+    let grid = screen_quadrilaterals::<f64, _>(3, &comm);
+
+    let loops = boundary_loops(&grid);
+    assert_eq!(loops.len(), 1);
+    assert_eq!(loops[0].len(), 12);
+}
+
+#[test]
+fn test_edge_chains_open_strip() {
+    // An open strip of edges 0-1-2-3-4-5 given in shuffled order, and a closed triangle
+    let edges = [
+        [2, 3],
+        [10, 11],
+        [4, 5],
+        [1, 0],
+        [12, 10],
+        [3, 4],
+        [11, 12],
+        [1, 2],
+    ];
+    let chains = edge_chains(&edges);
+    assert_eq!(chains.len(), 2);
+
+    let strip = chains.iter().find(|c| c.contains(&0)).unwrap();
+    assert_eq!(strip.len(), 6);
+    if strip[0] == 0 {
+        assert_eq!(*strip, vec![0, 1, 2, 3, 4, 5]);
+    } else {
+        assert_eq!(*strip, vec![5, 4, 3, 2, 1, 0]);
+    }
+
+    let triangle = chains.iter().find(|c| c.contains(&10)).unwrap();
+    assert_eq!(triangle.len(), 3);
+}
+
+#[test]
+fn test_areas_screen() {
+    let _ = *MPI_UNIVERSE;
+    let comm = mpi::topology::SimpleCommunicator::self_comm();
+    let triangles = screen_triangles::<f64, _>(3, &comm);
+    let quadrilaterals = screen_quadrilaterals::<f64, _>(3, &comm);
+
+    // The screens cover the square [0, 3/4]^2
+    assert_relative_eq!(surface_area(&triangles), 0.5625, epsilon = 1e-12);
+    assert_relative_eq!(surface_area(&quadrilaterals), 0.5625, epsilon = 1e-12);
+    for a in cell_areas(&quadrilaterals) {
+        assert_relative_eq!(a, 0.0625, epsilon = 1e-12);
+    }
+}
+
+#[test]
+fn test_curvature_sphere() {
+    let _ = *MPI_UNIVERSE;
+    let comm = mpi::topology::SimpleCommunicator::self_comm();
+    let grid = regular_sphere::<f64, _>(3, 1, &comm);
+
+    let area = surface_area(&grid);
+    assert_relative_eq!(area, 4.0 * std::f64::consts::PI, max_relative = 0.05);
+
+    let curvatures = vertex_curvatures(&grid);
+    for h in &curvatures.mean {
+        assert_relative_eq!(*h, 1.0, max_relative = 0.1);
+    }
+
+    // Gauss-Bonnet: the Gaussian curvature of a closed genus 0 surface integrates to 4 pi
+    let cell_values = cell_curvatures(&grid);
+    let total = cell_values
+        .gaussian
+        .iter()
+        .zip(cell_areas(&grid))
+        .map(|(k, a)| k * a)
+        .sum::<f64>();
+    assert_relative_eq!(total, 4.0 * std::f64::consts::PI, max_relative = 0.05);
+}
+
+#[test]
+#[should_panic(expected = "Quadrilateral")]
+fn test_curvature_quadrilaterals() {
+    let _ = *MPI_UNIVERSE;
+    let comm = mpi::topology::SimpleCommunicator::self_comm();
+    let grid = screen_quadrilaterals::<f64, _>(2, &comm);
+    let _ = vertex_curvatures(&grid);
+}
+
+#[test]
+fn test_gather_to_root_serial() {
+    let _ = *MPI_UNIVERSE;
+    let comm = mpi::topology::SimpleCommunicator::self_comm();
+    let grid = regular_sphere::<f64, _>(1, 1, &comm);
+
+    let serial_grid = gather_grid_to_root(&grid).unwrap();
+    assert_eq!(serial_grid.entity_count(ReferenceCellType::Point), 18);
+    assert_eq!(serial_grid.entity_count(ReferenceCellType::Triangle), 32);
+    assert_relative_eq!(
+        surface_area(&serial_grid),
+        surface_area(&grid),
+        epsilon = 1e-12
+    );
+
+    let element = LagrangeElementFamily::<f64>::new(1, Continuity::Standard);
+    let space = FunctionSpace::new(&grid, &element);
+    let values = (0..space.local_size())
+        .map(|i| space.global_dof_index(i) as f64)
+        .collect::<Vec<_>>();
+    let gathered = gather_to_root(&space, &values).unwrap();
+    assert_eq!(gathered.len(), space.global_size());
+    for (i, v) in gathered.iter().enumerate() {
+        assert_eq!(*v, i as f64);
+    }
+}
+
+#[test]
+fn test_heal_mesh() {
+    let points = [
+        0.0,
+        0.0,
+        0.0, //
+        1.0,
+        0.0,
+        0.0, //
+        0.0,
+        1.0,
+        0.0, //
+        1.0 + 1e-12,
+        0.0,
+        0.0, //
+        0.0,
+        1.0,
+        0.0, //
+        1.0,
+        1.0,
+        0.0, //
+        5.0,
+        5.0,
+        5.0, //
+        1.0,
+        1.0,
+        1e-9, //
+        1.0 + 1e-5,
+        1.0,
+        0.0, //
+    ];
+    // The second cell uses duplicated vertices, the third cell is a duplicate of the first, the
+    // fourth cell is degenerate once coincident vertices are merged and the last cell is a sliver
+    let cells = [0, 1, 2, 3, 5, 4, 0, 1, 2, 5, 7, 1, 1, 5, 8];
+
+    let healed = heal_mesh(&points, &cells, 1e-8, 1e-6);
+    assert_eq!(
+        healed.report,
+        HealingReport {
+            merged_vertices: 3,
+            collapsed_edges: 0,
+            removed_degenerate_cells: 1,
+            removed_duplicate_cells: 1,
+            removed_unused_vertices: 1,
+        }
+    );
+    assert_eq!(healed.cells, vec![0, 1, 2, 1, 3, 2, 1, 3, 4]);
+    assert_eq!(healed.points.len(), 15);
+
+    let grid = healed.create_grid();
+    assert_eq!(grid.entity_count(ReferenceCellType::Triangle), 3);
+    assert_relative_eq!(surface_area(&grid), 1.0 + 5e-6, epsilon = 1e-12);
+
+    // With a larger minimum edge length, the short edge of the sliver is collapsed
+    let healed = heal_mesh(&points, &cells, 1e-8, 1e-3);
+    assert_eq!(
+        healed.report,
+        HealingReport {
+            merged_vertices: 3,
+            collapsed_edges: 1,
+            removed_degenerate_cells: 2,
+            removed_duplicate_cells: 1,
+            removed_unused_vertices: 1,
+        }
+    );
+    assert_eq!(healed.cells, vec![0, 1, 2, 1, 3, 2]);
+}
+
+#[test]
+fn test_heal_mesh_not_transitive() {
+    // Each of the first three points is closer than the tolerance to the next, but the first and
+    // third points are further apart than the tolerance
+    let points = [
+        0.0, 0.0, 0.0, //
+        0.6, 0.0, 0.0, //
+        1.2, 0.0, 0.0, //
+        0.0, 5.0, 0.0, //
+        1.2, 5.0, 0.0, //
+    ];
+    let cells = [0, 2, 3, 2, 4, 3, 1, 2, 4];
+
+    let healed = heal_mesh(&points, &cells, 1.0, 0.0);
+    assert_eq!(healed.report.merged_vertices, 1);
+    assert_eq!(healed.cells, vec![0, 1, 2, 1, 3, 2, 0, 1, 3]);
+}
+
+#[test]
+fn test_heal_mesh_tiny_tolerance() {
+    // The coordinates divided by the tolerance do not fit in an i64
+    let points = [
+        0.0, 0.0, 0.0, //
+        1.0, 0.0, 0.0, //
+        0.0, 1.0, 0.0, //
+        1.0, 0.0, 0.0, //
+        1.0, 1.0, 0.0, //
+    ];
+    let cells = [0, 1, 2, 3, 4, 2];
+
+    let healed = heal_mesh(&points, &cells, 1e-30, 0.0);
+    assert_eq!(healed.report.merged_vertices, 1);
+    assert_eq!(healed.cells, vec![0, 1, 2, 1, 3, 2]);
+}
+
+#[test]
+fn test_volume_boundary_cube() {
+    let _ = *MPI_UNIVERSE;
+    // The unit cube split into six tetrahedra, with vertex x + 2y + 4z at (x, y, z)
+    let points = (0..8)
+        .flat_map(|v| [(v % 2) as f64, ((v / 2) % 2) as f64, (v / 4) as f64])
+        .collect::<Vec<_>>();
+    let cells = [
+        0, 1, 3, 7, 0, 1, 5, 7, 0, 2, 3, 7, 0, 2, 6, 7, 0, 4, 5, 7, 0, 4, 6, 7,
+    ];
+
+    let boundary = volume_boundary(&points, &cells);
+    assert_eq!(boundary.cells.len(), 36);
+    assert_eq!(boundary.volume_vertices.len(), 8);
+    for (i, v) in boundary.volume_vertices.iter().enumerate() {
+        assert_eq!(boundary.points[3 * i..3 * i + 3], points[3 * v..3 * v + 3]);
+    }
+
+    // The normals point outwards, so the integral of x.n over the surface is 3 times the volume
+    let mut flux = 0.0;
+    for c in boundary.cells.chunks(3) {
+        let p = |i: usize| &boundary.points[3 * c[i]..3 * c[i] + 3];
+        let a = [0, 1, 2].map(|j| p(1)[j] - p(0)[j]);
+        let b = [0, 1, 2].map(|j| p(2)[j] - p(0)[j]);
+        let n = [
+            a[1] * b[2] - a[2] * b[1],
+            a[2] * b[0] - a[0] * b[2],
+            a[0] * b[1] - a[1] * b[0],
+        ];
+        flux += (0..3).map(|j| p(0)[j] * n[j]).sum::<f64>() / 2.0;
+    }
+    assert_relative_eq!(flux, 3.0, epsilon = 1e-12);
+
+    let grid = boundary.create_grid();
+    assert_relative_eq!(surface_area(&grid), 6.0, epsilon = 1e-12);
+
+    let element = LagrangeElementFamily::<f64>::new(1, Continuity::Standard);
+    let space = FunctionSpace::new(&grid, &element);
+    let trace = trace_matrix(&space, &boundary.volume_vertices, 8);
+    assert_eq!(trace.shape(), [8, 8]);
+    // Each DOF is the trace of exactly one volume vertex, and each volume vertex is on the boundary
+    assert_eq!(trace.indptr(), &(0..9).collect::<Vec<_>>());
+    assert!(trace.data().iter().all(|v| *v == 1.0));
+    let mut cols = trace.indices().to_vec();
+    cols.sort();
+    assert_eq!(cols, (0..8).collect::<Vec<_>>());
+}
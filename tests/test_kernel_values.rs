@@ -0,0 +1,75 @@
+use approx::*;
+use bempp::boundary_assemblers::BoundaryAssemblerOptions;
+use bempp::{helmholtz, laplace};
+use cauchy::c64;
+use rlst::{RandomAccessByRef, Shape};
+use std::f64::consts::PI;
+
+#[test]
+fn test_laplace_kernel_values() {
+    let options = BoundaryAssemblerOptions::default();
+    let assembler = laplace::assembler::single_layer::<f64>(&options);
+
+    let sources = [0.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+    let targets = [0.0, 2.0, 0.0, 1.0, 0.0, 3.0, 0.5, 0.5, 0.5];
+    let values = assembler.kernel_values(&sources, &targets);
+    assert_eq!(values.shape(), [1, 2, 3]);
+    for (i, s) in sources.chunks(3).enumerate() {
+        for (j, t) in targets.chunks(3).enumerate() {
+            let r = (0..3).map(|d| (s[d] - t[d]).powi(2)).sum::<f64>().sqrt();
+            assert_relative_eq!(
+                *values.get([0, i, j]).unwrap(),
+                1.0 / (4.0 * PI * r),
+                epsilon = 1e-12
+            );
+        }
+    }
+
+    let pairwise = assembler.kernel_values_pairwise(&sources, &targets[..6]);
+    assert_eq!(pairwise.shape(), [1, 2]);
+    assert_relative_eq!(
+        *pairwise.get([0, 0]).unwrap(),
+        1.0 / (8.0 * PI),
+        epsilon = 1e-12
+    );
+    assert_relative_eq!(
+        *pairwise.get([0, 1]).unwrap(),
+        1.0 / (12.0 * PI),
+        epsilon = 1e-12
+    );
+}
+
+#[test]
+fn test_laplace_kernel_derivatives() {
+    let options = BoundaryAssemblerOptions::default();
+    let assembler = laplace::assembler::double_layer::<f64>(&options);
+
+    let values = assembler.kernel_values_pairwise(&[0.0, 0.0, 0.0], &[0.0, 0.0, 2.0]);
+    assert_eq!(values.shape(), [4, 1]);
+    assert_relative_eq!(
+        *values.get([0, 0]).unwrap(),
+        1.0 / (8.0 * PI),
+        epsilon = 1e-12
+    );
+    // The gradient points along the line between the points and has size 1 / (4 pi r^2)
+    assert_relative_eq!(*values.get([1, 0]).unwrap(), 0.0, epsilon = 1e-12);
+    assert_relative_eq!(*values.get([2, 0]).unwrap(), 0.0, epsilon = 1e-12);
+    assert_relative_eq!(
+        values.get([3, 0]).unwrap().abs(),
+        1.0 / (16.0 * PI),
+        epsilon = 1e-12
+    );
+}
+
+#[test]
+fn test_helmholtz_kernel_values() {
+    let options = BoundaryAssemblerOptions::default();
+    let k = 3.0;
+    let assembler = helmholtz::assembler::single_layer::<c64>(k, &options);
+
+    let r = 0.7;
+    let values = assembler.kernel_values_pairwise(&[0.0, 0.0, 0.0], &[r, 0.0, 0.0]);
+    let expected = c64::new(0.0, k * r).exp() / (4.0 * PI * r);
+    assert_relative_eq!(values.get([0, 0]).unwrap().re, expected.re, epsilon = 1e-12);
+    assert_relative_eq!(values.get([0, 0]).unwrap().im, expected.im, epsilon = 1e-12);
+}
@@ -0,0 +1,39 @@
+use approx::*;
+use bempp::norm_estimation::{
+    energy_norm, estimate_condition_number, estimate_dense_norm, estimate_norm,
+};
+use rlst::{rlst_dynamic_array2, RawAccessMut};
+
+#[test]
+fn test_norm_diagonal() {
+    let diag = [3.0, 1.0, 0.5, -2.0];
+    let apply = |x: &[f64], y: &mut [f64]| {
+        for (yi, xi, d) in itertools::izip!(y.iter_mut(), x, &diag) {
+            *yi = d * xi;
+        }
+    };
+    let solve = |x: &[f64], y: &mut [f64]| {
+        for (yi, xi, d) in itertools::izip!(y.iter_mut(), x, &diag) {
+            *yi = xi / d;
+        }
+    };
+
+    let norm = estimate_norm([4, 4], apply, apply, 100, 1e-12);
+    assert_relative_eq!(norm, 3.0, epsilon = 1e-6);
+
+    let cond = estimate_condition_number(4, apply, apply, solve, solve, 100, 1e-12);
+    assert_relative_eq!(cond, 6.0, epsilon = 1e-5);
+
+    let u = [1.0, 1.0, 0.0, 0.0];
+    let identity = |x: &[f64], y: &mut [f64]| y.copy_from_slice(x);
+    assert_relative_eq!(energy_norm(identity, &u), f64::sqrt(2.0), epsilon = 1e-14);
+}
+
+#[test]
+fn test_norm_dense() {
+    let mut matrix = rlst_dynamic_array2!(f64, [2, 2]);
+    matrix.data_mut().copy_from_slice(&[1.0, 0.0, 2.0, 3.0]);
+
+    let norm = estimate_dense_norm(&matrix, 100, 1e-14);
+    assert_relative_eq!(norm, f64::sqrt(7.0 + f64::sqrt(40.0)), epsilon = 1e-6);
+}
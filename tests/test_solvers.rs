@@ -0,0 +1,441 @@
+use approx::*;
+use bempp::boundary_assemblers::BoundaryAssemblerOptions;
+use bempp::function::{FunctionSpace, FunctionSpaceTrait};
+use bempp::solvers::{
+    block_cg, block_pcg, cocg, deflated_block_cg, deflated_block_pcg, SolverOptions,
+};
+use bempp::{helmholtz, laplace};
+use cauchy::c64;
+use mpi::environment::Universe;
+use ndelement::ciarlet::LagrangeElementFamily;
+use ndelement::types::Continuity;
+use rlst::RawAccess;
+use std::sync::LazyLock;
+
+static MPI_UNIVERSE: LazyLock<Universe> = std::sync::LazyLock::new(|| {
+    mpi::initialize_with_threading(mpi::Threading::Multiple)
+        .unwrap()
+        .0
+});
+
+/// Apply a dense column-major matrix to a block of vectors
+fn dense_apply(matrix: &[f64], size: usize, x: &[f64], y: &mut [f64]) {
+    for (xcol, ycol) in x.chunks(size).zip(y.chunks_mut(size)) {
+        for (i, yi) in ycol.iter_mut().enumerate() {
+            *yi = xcol
+                .iter()
+                .enumerate()
+                .map(|(j, xj)| matrix[j * size + i] * xj)
+                .sum();
+        }
+    }
+}
+
+#[test]
+fn test_block_cg_tridiagonal() {
+    let size = 20;
+    let mut matrix = vec![0.0; size * size];
+    for i in 0..size {
+        matrix[i * size + i] = 2.0;
+        if i > 0 {
+            matrix[i * size + i - 1] = -1.0;
+            matrix[(i - 1) * size + i] = -1.0;
+        }
+    }
+    let b = (0..3 * size)
+        .map(|i| 1.0 + (i % 7) as f64)
+        .collect::<Vec<_>>();
+    let mut x = vec![0.0; 3 * size];
+
+    let mut options = SolverOptions::default();
+    options.set_tolerance(1e-10);
+    let result = block_cg(
+        size,
+        |x, y| dense_apply(&matrix, size, x, y),
+        &b,
+        &mut x,
+        &options,
+    );
+    assert!(result.converged);
+    assert!(result.iterations <= size);
+    assert_eq!(result.history.relative_residuals.len(), result.iterations);
+    assert_eq!(result.history.apply_times.len(), result.iterations);
+    assert!(result.history.preconditioner_times.is_empty());
+
+    let mut ax = vec![0.0; 3 * size];
+    dense_apply(&matrix, size, &x, &mut ax);
+    for (i, j) in ax.iter().zip(&b) {
+        assert_relative_eq!(i, j, epsilon = 1e-8);
+    }
+}
+
+#[test]
+fn test_block_cg_dependent_right_hand_sides() {
+    let size = 20;
+    let mut matrix = vec![0.0; size * size];
+    for i in 0..size {
+        matrix[i * size + i] = 2.0;
+        if i > 0 {
+            matrix[i * size + i - 1] = -1.0;
+            matrix[(i - 1) * size + i] = -1.0;
+        }
+    }
+    // The second right-hand side is a multiple of the first, and the third is an eigenvector of
+    // the matrix, so it converges after one iteration
+    let mut b = (0..size).map(|i| 1.0 + (i % 7) as f64).collect::<Vec<_>>();
+    b.extend((0..size).map(|i| 2.0 * b[i]).collect::<Vec<_>>());
+    b.extend(
+        (0..size).map(|i| (3.0 * std::f64::consts::PI * (i + 1) as f64 / (size + 1) as f64).sin()),
+    );
+    let mut x = vec![0.0; 3 * size];
+
+    let mut options = SolverOptions::default();
+    options.set_tolerance(1e-10);
+    let result = block_cg(
+        size,
+        |x, y| dense_apply(&matrix, size, x, y),
+        &b,
+        &mut x,
+        &options,
+    );
+    assert!(result.converged);
+    assert!(result.iterations <= size);
+    assert!(result.history.relative_residuals[0][2] <= 1e-10);
+
+    let mut ax = vec![0.0; 3 * size];
+    dense_apply(&matrix, size, &x, &mut ax);
+    for (i, j) in ax.iter().zip(&b) {
+        assert_relative_eq!(i, j, epsilon = 1e-8);
+    }
+}
+
+#[test]
+fn test_block_cg_single_layer() {
+    let _ = *MPI_UNIVERSE;
+    let comm = mpi::topology::SimpleCommunicator::self_comm();
+    let grid = bempp::shapes::regular_sphere(1, 1, &comm);
+    let element = LagrangeElementFamily::<f64>::new(0, Continuity::Discontinuous);
+    let space = FunctionSpace::new(&grid, &element);
+    let options = BoundaryAssemblerOptions::default();
+    let matrix = laplace::assembler::single_layer(&options).assemble(&space, &space);
+    let size = space.global_size();
+
+    let b = (0..2 * size)
+        .map(|i| if i < size { 1.0 } else { (i % 3) as f64 - 0.5 })
+        .collect::<Vec<_>>();
+    let mut x = vec![0.0; 2 * size];
+    let result = block_cg(
+        size,
+        |x, y| dense_apply(matrix.data(), size, x, y),
+        &b,
+        &mut x,
+        &SolverOptions::default(),
+    );
+    assert!(result.converged);
+    for r in &result.relative_residuals {
+        assert!(*r <= 1e-8);
+    }
+}
+
+#[test]
+fn test_block_pcg_jacobi() {
+    let size = 20;
+    let diagonal = (0..size).map(|i| 2.0 + i as f64).collect::<Vec<_>>();
+    let mut matrix = vec![0.0; size * size];
+    for (i, d) in diagonal.iter().enumerate() {
+        matrix[i * size + i] = *d;
+        if i > 0 {
+            matrix[i * size + i - 1] = -1.0;
+            matrix[(i - 1) * size + i] = -1.0;
+        }
+    }
+    let b = (0..2 * size)
+        .map(|i| 1.0 + (i % 5) as f64)
+        .collect::<Vec<_>>();
+    let mut x = vec![0.0; 2 * size];
+
+    let result = block_pcg(
+        size,
+        |x, y| dense_apply(&matrix, size, x, y),
+        |r, z| {
+            for (rcol, zcol) in r.chunks(size).zip(z.chunks_mut(size)) {
+                for (zi, ri, d) in itertools::izip!(zcol.iter_mut(), rcol, &diagonal) {
+                    *zi = ri / d;
+                }
+            }
+        },
+        &b,
+        &mut x,
+        &SolverOptions::default(),
+    );
+    assert!(result.converged);
+    assert_eq!(result.history.preconditioner_times.len(), result.iterations);
+
+    let mut ax = vec![0.0; 2 * size];
+    dense_apply(&matrix, size, &x, &mut ax);
+    for (i, j) in ax.iter().zip(&b) {
+        assert_relative_eq!(i, j, epsilon = 1e-6);
+    }
+}
+
+#[test]
+fn test_block_cg_initial_guess() {
+    let size = 30;
+    let tridiagonal = |diagonal: f64| {
+        let mut matrix = vec![0.0; size * size];
+        for i in 0..size {
+            matrix[i * size + i] = diagonal;
+            if i > 0 {
+                matrix[i * size + i - 1] = -1.0;
+                matrix[(i - 1) * size + i] = -1.0;
+            }
+        }
+        matrix
+    };
+    let b = (0..size).map(|i| 1.0 + (i % 5) as f64).collect::<Vec<_>>();
+    let mut options = SolverOptions::default();
+    options.set_tolerance(1e-10);
+
+    // Solve a sequence of nearby problems, as in a parameter sweep
+    let first = tridiagonal(2.5);
+    let mut x = vec![0.0; size];
+    let result = block_cg(
+        size,
+        |x, y| dense_apply(&first, size, x, y),
+        &b,
+        &mut x,
+        &options,
+    );
+    assert!(result.converged);
+
+    let second = tridiagonal(2.55);
+    let mut cold = vec![0.0; size];
+    let cold_result = block_cg(
+        size,
+        |x, y| dense_apply(&second, size, x, y),
+        &b,
+        &mut cold,
+        &options,
+    );
+    options.set_use_initial_guess(true);
+    let warm_result = block_cg(
+        size,
+        |x, y| dense_apply(&second, size, x, y),
+        &b,
+        &mut x,
+        &options,
+    );
+    assert!(cold_result.converged);
+    assert!(warm_result.converged);
+    assert!(warm_result.iterations < cold_result.iterations);
+    // The initial residual needs one extra application of the operator
+    assert_eq!(
+        warm_result.history.apply_times.len(),
+        warm_result.iterations + 1
+    );
+    for (i, j) in x.iter().zip(&cold) {
+        assert_relative_eq!(i, j, epsilon = 1e-8);
+    }
+
+    // Starting from the solution, no iterations are needed
+    let result = block_cg(
+        size,
+        |x, y| dense_apply(&second, size, x, y),
+        &b,
+        &mut x,
+        &options,
+    );
+    assert!(result.converged);
+    assert_eq!(result.iterations, 0);
+}
+
+#[test]
+fn test_deflated_block_cg() {
+    let size = 200;
+    let mut matrix = vec![0.0; size * size];
+    for i in 0..size {
+        matrix[i * size + i] = 2.0;
+        if i > 0 {
+            matrix[i * size + i - 1] = -1.0;
+            matrix[(i - 1) * size + i] = -1.0;
+        }
+    }
+    // The eigenvectors of the three smallest eigenvalues
+    let deflation = (1..4)
+        .flat_map(|k| {
+            (0..size).map(move |i| {
+                (k as f64 * std::f64::consts::PI * (i + 1) as f64 / (size + 1) as f64).sin()
+            })
+        })
+        .collect::<Vec<_>>();
+    let b = (0..2 * size)
+        .map(|i| 1.0 + (i % 7) as f64)
+        .collect::<Vec<_>>();
+    let mut options = SolverOptions::default();
+    options.set_tolerance(1e-10);
+
+    let mut x = vec![0.0; 2 * size];
+    let result = block_cg(
+        size,
+        |x, y| dense_apply(&matrix, size, x, y),
+        &b,
+        &mut x,
+        &options,
+    );
+    let mut deflated_x = vec![0.0; 2 * size];
+    let deflated_result = deflated_block_cg(
+        size,
+        |x, y| dense_apply(&matrix, size, x, y),
+        &deflation,
+        &b,
+        &mut deflated_x,
+        &options,
+    );
+    assert!(result.converged);
+    assert!(deflated_result.converged);
+    assert!(deflated_result.iterations < result.iterations);
+    // The deflation space needs one extra application of the operator
+    assert_eq!(
+        deflated_result.history.apply_times.len(),
+        deflated_result.iterations + 1
+    );
+    let mut ax = vec![0.0; 2 * size];
+    dense_apply(&matrix, size, &deflated_x, &mut ax);
+    for (i, j) in ax.iter().zip(&b) {
+        assert_relative_eq!(i, j, epsilon = 1e-6);
+    }
+
+    let mut preconditioned_x = vec![0.0; 2 * size];
+    let preconditioned_result = deflated_block_pcg(
+        size,
+        |x, y| dense_apply(&matrix, size, x, y),
+        |r, z| {
+            for (zi, ri) in z.iter_mut().zip(r) {
+                *zi = ri / 2.0;
+            }
+        },
+        &deflation,
+        &b,
+        &mut preconditioned_x,
+        &options,
+    );
+    assert!(preconditioned_result.converged);
+    dense_apply(&matrix, size, &preconditioned_x, &mut ax);
+    for (i, j) in ax.iter().zip(&b) {
+        assert_relative_eq!(i, j, epsilon = 1e-6);
+    }
+}
+
+/// Apply a dense column-major complex matrix to a block of vectors
+fn dense_apply_complex(matrix: &[c64], size: usize, x: &[c64], y: &mut [c64]) {
+    for (xcol, ycol) in x.chunks(size).zip(y.chunks_mut(size)) {
+        for (i, yi) in ycol.iter_mut().enumerate() {
+            *yi = xcol
+                .iter()
+                .enumerate()
+                .map(|(j, xj)| matrix[j * size + i] * xj)
+                .sum();
+        }
+    }
+}
+
+#[test]
+fn test_cocg_complex_symmetric() {
+    let size = 20;
+    let mut matrix = vec![c64::new(0.0, 0.0); size * size];
+    for i in 0..size {
+        matrix[i * size + i] = c64::new(2.5, 0.5);
+        if i > 0 {
+            matrix[i * size + i - 1] = c64::new(-1.0, 0.1);
+            matrix[(i - 1) * size + i] = c64::new(-1.0, 0.1);
+        }
+    }
+    let b = (0..2 * size)
+        .map(|i| c64::new(1.0 + (i % 3) as f64, (i % 2) as f64))
+        .collect::<Vec<_>>();
+    let mut x = vec![c64::new(0.0, 0.0); 2 * size];
+
+    let mut options = SolverOptions::default();
+    options.set_tolerance(1e-10);
+    let result = cocg(
+        size,
+        |x, y| dense_apply_complex(&matrix, size, x, y),
+        &b,
+        &mut x,
+        &options,
+    );
+    assert!(result.converged);
+
+    let mut ax = vec![c64::new(0.0, 0.0); 2 * size];
+    dense_apply_complex(&matrix, size, &x, &mut ax);
+    for (i, j) in ax.iter().zip(&b) {
+        assert_relative_eq!(i.re, j.re, epsilon = 1e-8);
+        assert_relative_eq!(i.im, j.im, epsilon = 1e-8);
+    }
+}
+
+#[test]
+fn test_cocg_initial_guess() {
+    let size = 20;
+    let mut matrix = vec![c64::new(0.0, 0.0); size * size];
+    for i in 0..size {
+        matrix[i * size + i] = c64::new(2.5, 0.5);
+        if i > 0 {
+            matrix[i * size + i - 1] = c64::new(-1.0, 0.1);
+            matrix[(i - 1) * size + i] = c64::new(-1.0, 0.1);
+        }
+    }
+    let b = (0..size)
+        .map(|i| c64::new(1.0 + (i % 3) as f64, (i % 2) as f64))
+        .collect::<Vec<_>>();
+    let mut x = vec![c64::new(0.0, 0.0); size];
+
+    let mut options = SolverOptions::default();
+    options.set_tolerance(1e-10);
+    let result = cocg(
+        size,
+        |x, y| dense_apply_complex(&matrix, size, x, y),
+        &b,
+        &mut x,
+        &options,
+    );
+    assert!(result.converged);
+    assert!(result.iterations > 0);
+
+    options.set_use_initial_guess(true);
+    let result = cocg(
+        size,
+        |x, y| dense_apply_complex(&matrix, size, x, y),
+        &b,
+        &mut x,
+        &options,
+    );
+    assert!(result.converged);
+    assert_eq!(result.iterations, 0);
+}
+
+#[test]
+fn test_cocg_helmholtz_single_layer() {
+    let _ = *MPI_UNIVERSE;
+    let comm = mpi::topology::SimpleCommunicator::self_comm();
+    let grid = bempp::shapes::regular_sphere(1, 1, &comm);
+    let element = LagrangeElementFamily::<c64>::new(0, Continuity::Discontinuous);
+    let space = FunctionSpace::new(&grid, &element);
+    let options = BoundaryAssemblerOptions::default();
+    let matrix = helmholtz::assembler::single_layer(1.5, &options).assemble(&space, &space);
+    let size = space.global_size();
+
+    let b = vec![c64::new(1.0, 0.0); size];
+    let mut x = vec![c64::new(0.0, 0.0); size];
+    let mut solver_options = SolverOptions::default();
+    solver_options.set_tolerance(1e-6);
+    let result = cocg(
+        size,
+        |x, y| dense_apply_complex(matrix.data(), size, x, y),
+        &b,
+        &mut x,
+        &solver_options,
+    );
+    assert!(result.converged);
+    assert!(result.iterations < size);
+}
@@ -1,8 +1,13 @@
-use bempp::function::{assign_dofs, FunctionSpace, FunctionSpaceTrait};
+use approx::*;
+use bempp::boundary_assemblers::BoundaryAssemblerOptions;
+use bempp::function::{assign_dofs, assign_dofs_in_regions, FunctionSpace, FunctionSpaceTrait};
+use bempp::grid_tools::{regions_from_edges, vertex_coordinates};
+use bempp::laplace;
 use bempp::shapes::{regular_sphere, screen_triangles};
 use ndelement::ciarlet::{LagrangeElementFamily, RaviartThomasElementFamily};
 use ndelement::types::{Continuity, ReferenceCellType};
 use ndgrid::traits::{Entity, Grid, Topology};
+use rlst::{RandomAccessByRef, Shape};
 use std::sync::LazyLock;
 
 use mpi::environment::Universe;
@@ -316,3 +321,132 @@ fn test_p3_mixed() {
     run_test(&grid, 3, Continuity::Standard);
 }
 */
+
+#[test]
+fn test_regions_sphere() {
+    let _ = *MPI_UNIVERSE;
+    let comm = mpi::topology::SimpleCommunicator::self_comm();
+    let grid = regular_sphere::<f64, _>(1, 1, &comm);
+    let regions = regions_from_edges(&grid, &[]);
+    assert!(regions.iter().all(|r| *r == 0));
+
+    let element = LagrangeElementFamily::<f64>::new(1, Continuity::Standard);
+    let space = FunctionSpace::new(&grid, &element);
+    let region_space = FunctionSpace::new_with_regions(&grid, &element, &regions);
+    assert_eq!(region_space.global_size(), space.global_size());
+}
+
+#[test]
+#[should_panic(expected = "one entry for each cell of the local grid")]
+fn test_regions_wrong_length() {
+    let _ = *MPI_UNIVERSE;
+    let comm = mpi::topology::SimpleCommunicator::self_comm();
+    let grid = regular_sphere::<f64, _>(1, 1, &comm);
+    let mut regions = regions_from_edges(&grid, &[]);
+    regions.pop();
+
+    let element = LagrangeElementFamily::<f64>::new(1, Continuity::Standard);
+    let _ = FunctionSpace::new_with_regions(&grid, &element, &regions);
+}
+
+#[test]
+fn test_regions_screen() {
+    let _ = *MPI_UNIVERSE;
+    let comm = mpi::topology::SimpleCommunicator::self_comm();
+    let grid = screen_triangles::<f64, _>(4, &comm);
+
+    // Split the screen along the line x = 2/5
+    let coordinates = vertex_coordinates(&grid);
+    let interface = grid
+        .entity_iter(1)
+        .filter(|edge| {
+            edge.topology()
+                .sub_entity_iter(0)
+                .all(|v| (coordinates[v][0] - 2.0 / 5.0).abs() < 1e-10)
+        })
+        .map(|edge| edge.local_index())
+        .collect::<Vec<_>>();
+    assert_eq!(interface.len(), 4);
+    let regions = regions_from_edges(&grid, &interface);
+    assert_eq!(regions.iter().filter(|r| **r == 0).count(), 16);
+    assert_eq!(regions.iter().filter(|r| **r == 1).count(), 16);
+
+    let element = LagrangeElementFamily::<f64>::new(1, Continuity::Standard);
+    let space = FunctionSpace::new(&grid, &element);
+    let region_space = FunctionSpace::new_with_regions(&grid, &element, &regions);
+    assert_eq!(space.global_size(), 25);
+    // The five vertices on the interface have a DOF for each region
+    assert_eq!(region_space.global_size(), 30);
+
+    // A continuous basis function is the sum of the basis functions of each region at its vertex,
+    // so summing the entries of the regional matrix gives the continuous matrix
+    let mut continuous_dof = vec![0; region_space.global_size()];
+    for cell in grid.entity_iter(2) {
+        let index = cell.local_index();
+        for (r, c) in region_space
+            .cell_dofs(index)
+            .unwrap()
+            .iter()
+            .zip(space.cell_dofs(index).unwrap())
+        {
+            continuous_dof[*r] = *c;
+        }
+    }
+    let options = BoundaryAssemblerOptions::default();
+    let assembler = laplace::assembler::single_layer(&options);
+    let matrix = assembler.assemble(&space, &space);
+    let region_matrix = assembler.assemble(&region_space, &region_space);
+    assert_eq!(region_matrix.shape(), [30, 30]);
+    let mut summed = vec![0.0; 25 * 25];
+    for (i, ci) in continuous_dof.iter().enumerate() {
+        for (j, cj) in continuous_dof.iter().enumerate() {
+            summed[ci * 25 + cj] += *region_matrix.get([i, j]).unwrap();
+        }
+    }
+    for i in 0..25 {
+        for j in 0..25 {
+            assert_relative_eq!(
+                summed[i * 25 + j],
+                *matrix.get([i, j]).unwrap(),
+                epsilon = 1e-12
+            );
+        }
+    }
+}
+
+#[test]
+fn test_regions_owner_data() {
+    let _ = *MPI_UNIVERSE;
+    let comm = mpi::topology::SimpleCommunicator::self_comm();
+    let grid = screen_triangles::<f64, _>(4, &comm);
+
+    // Split the screen along the line x = 2/5
+    let coordinates = vertex_coordinates(&grid);
+    let on_interface = |v: usize| (coordinates[v][0] - 2.0 / 5.0).abs() < 1e-10;
+    let interface = grid
+        .entity_iter(1)
+        .filter(|edge| edge.topology().sub_entity_iter(0).all(on_interface))
+        .map(|edge| edge.local_index())
+        .collect::<Vec<_>>();
+    let regions = regions_from_edges(&grid, &interface);
+
+    let element = LagrangeElementFamily::<f64>::new(1, Continuity::Standard);
+    let (_cell_dofs, entity_dofs, size, owner_data) =
+        assign_dofs_in_regions(0, &grid, &element, Some(&regions));
+    assert_eq!(size, 30);
+    assert_eq!(owner_data.len(), size);
+
+    // Each DOF of a vertex shared by both regions has its own owner data
+    for v in 0..coordinates.len() {
+        let vertex_data = owner_data
+            .iter()
+            .filter(|o| o.1 == 0 && o.2 == v)
+            .collect::<Vec<_>>();
+        let ndofs = if on_interface(v) { 2 } else { 1 };
+        assert_eq!(entity_dofs[0][v].len(), ndofs);
+        assert_eq!(vertex_data.len(), ndofs);
+        for (i, o) in vertex_data.iter().enumerate() {
+            assert_eq!(**o, (0, 0, v, i));
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use approx::*;
+use bempp::units::{check_resolution, Frequency, Length, LengthUnit, MeshUnits, Wavenumber};
+use mpi::environment::Universe;
+use std::sync::LazyLock;
+
+static MPI_UNIVERSE: LazyLock<Universe> = std::sync::LazyLock::new(|| {
+    mpi::initialize_with_threading(mpi::Threading::Multiple)
+        .unwrap()
+        .0
+});
+
+#[test]
+fn test_unit_conversion() {
+    let length = Length::new(2.5, LengthUnit::Centimetre);
+    assert_relative_eq!(length.value(LengthUnit::Millimetre), 25.0, epsilon = 1e-12);
+
+    let k = Wavenumber::new(2.0, LengthUnit::Centimetre);
+    assert_relative_eq!(k.value(LengthUnit::Metre), 200.0, epsilon = 1e-12);
+
+    let units = MeshUnits::new(LengthUnit::Millimetre);
+    assert_relative_eq!(units.wavenumber(k), 0.2, epsilon = 1e-12);
+    assert_relative_eq!(
+        units
+            .dimensional_wavenumber(0.2)
+            .value(LengthUnit::Centimetre),
+        2.0,
+        epsilon = 1e-12
+    );
+
+    // Sound in air at 343Hz has a wavelength of 1m
+    let k = Wavenumber::from_frequency(Frequency::from_hertz(343.0), 343.0);
+    assert_relative_eq!(
+        k.wavelength().value(LengthUnit::Metre),
+        1.0,
+        epsilon = 1e-12
+    );
+}
+
+#[test]
+fn test_check_resolution() {
+    let _ = *MPI_UNIVERSE;
+    let comm = mpi::topology::SimpleCommunicator::self_comm();
+    let grid = bempp::shapes::regular_sphere::<f64, _>(1, 1, &comm);
+
+    let units = MeshUnits::new(LengthUnit::Millimetre);
+    let k = Wavenumber::new(1.0, LengthUnit::Millimetre);
+    assert!(check_resolution(&grid, &units, k, 5.0).is_ok());
+
+    // The same value treated as a wavenumber per micrometre is not resolved by the grid
+    let k = Wavenumber::new(1.0, LengthUnit::Micrometre);
+    assert!(check_resolution(&grid, &units, k, 5.0).is_err());
+}